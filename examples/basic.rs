@@ -77,16 +77,7 @@ fn main() {
     let argv: Vec<OsString> = std::env::args_os().skip(1).collect();
     let mut ctx = Ctx::default();
     let matches = match rapp::parse(&env, &root, &argv, &mut ctx) {
-        Err(rapp::Error::ExitMsg { code, message }) => {
-            if let Some(msg) = message {
-                print!("{msg}");
-            }
-            std::process::exit(code);
-        }
-        Err(e) => {
-            eprintln!("error: {e}");
-            std::process::exit(2);
-        }
+        Err(e) => e.report(&env, &root),
         Ok(m) => m,
     };
 
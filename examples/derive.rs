@@ -0,0 +1,65 @@
+//! The `git.rs` example, trimmed down and written with `#[derive(Args)]` / `#[derive(Subcommands)]`.
+//!
+//! Run with `cargo run --example derive --features derive -- commit -am "msg"`.
+
+use rust_args_parser as rapp;
+use rust_args_parser::Args as _;
+use std::path::PathBuf;
+
+/// A tiny git-like CLI
+#[derive(rapp::Args, Default, Debug)]
+#[command(name = "git")]
+struct Git {
+    /// Run as if started in <path>
+    #[arg(short = 'C', long = "chdir", metavar = "PATH", global)]
+    chdirs: Vec<PathBuf>,
+    /// Do not pipe output into a pager
+    #[arg(global)]
+    no_pager: bool,
+    #[command(subcommand)]
+    cmd: Option<GitCmd>,
+}
+
+#[derive(rapp::Subcommands, Debug)]
+enum GitCmd {
+    /// Create an empty repository
+    Init(Init),
+    /// Record changes to the repository
+    #[command(alias = "ci")]
+    Commit(Commit),
+    /// Show the working tree status
+    #[command(alias = "st")]
+    Status,
+}
+
+#[derive(rapp::Args, Default, Debug)]
+struct Init {
+    /// Create a bare repository
+    bare: bool,
+    /// Where to create the repository
+    #[arg(positional)]
+    dir: Option<PathBuf>,
+}
+
+#[derive(rapp::Args, Default, Debug)]
+struct Commit {
+    /// Commit all changed files
+    #[arg(short)]
+    all: bool,
+    /// Commit message
+    #[arg(short)]
+    message: Option<String>,
+    /// Be more verbose (repeatable)
+    #[arg(short, count)]
+    verbose: u8,
+}
+
+fn main() {
+    let env = rapp::Env { version: Some("2.0.0"), ..Default::default() };
+    let argv: Vec<std::ffi::OsString> = std::env::args_os().skip(1).collect();
+
+    match Git::parse_from(&env, &argv) {
+        Ok(git) => println!("{git:?}"),
+        Err(e) => e.report(&env, &Git::command()),
+    }
+}
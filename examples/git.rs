@@ -3,7 +3,7 @@ use std::ffi::OsStr;
 use std::path::PathBuf;
 
 #[derive(Default, Debug)]
-struct GitCtx {
+pub struct GitCtx {
     global: Global,
     exec: Option<GitExec>,
 }
@@ -234,8 +234,13 @@ fn set_checkout_branch(v: &OsStr, c: &mut GitCtx) {
     ensure_checkout(c).branch = Some(v.to_string_lossy().into_owned());
 }
 
+// Dynamic completion for `git __complete ...` (see the `completions` feature).
+fn complete_branch(partial: &str) -> Vec<rapp::Candidate> {
+    ["main", "develop", "release"].iter().filter(|b| b.starts_with(partial)).map(|b| rapp::Candidate::new(*b)).collect()
+}
+
 #[allow(clippy::too_many_lines)]
-fn build_spec<'a>() -> rapp::CmdSpec<'a, GitCtx> {
+pub fn build_spec<'a>() -> rapp::CmdSpec<'a, GitCtx> {
     use rapp::{CmdSpec, GroupMode, OptSpec, PosSpec};
 
     // Root (global options)
@@ -294,7 +299,7 @@ fn build_spec<'a>() -> rapp::CmdSpec<'a, GitCtx> {
                 })
                 .validator(|m| {
                     // Example policy: require at least one -m (like scripting UX)
-                    if !m.view().values("message").map_or(false, |v| !v.is_empty()) {
+                    if !m.view().values("message").is_some_and(|v| !v.is_empty()) {
                         return Err("commit requires at least one -m MSG in this demo");
                     }
                     Ok(())
@@ -335,7 +340,7 @@ fn build_spec<'a>() -> rapp::CmdSpec<'a, GitCtx> {
         .subcmd(
             CmdSpec::<'a, GitCtx>::new("checkout")
                 .opt(OptSpec::value("new", set_checkout_new).short('b').metavar("NAME"))
-                .pos(PosSpec::new("BRANCH", set_checkout_branch).range(0, 1))
+                .pos(PosSpec::new("BRANCH", set_checkout_branch).range(0, 1).completer(complete_branch))
                 .handler(|_, c| {
                     ensure_checkout(c);
                 })
@@ -352,7 +357,12 @@ fn build_spec<'a>() -> rapp::CmdSpec<'a, GitCtx> {
 }
 
 fn main() {
-    let mut env = rapp::Env { version: Some("2.0.0"), author: Some("The Demo Team"), ..Default::default() };
+    let mut env = rapp::Env {
+        version: Some("2.0.0"),
+        author: Some("The Demo Team"),
+        dynamic_completions: true,
+        ..Default::default()
+    };
     env.wrap_cols = 100;
 
     let argv: Vec<std::ffi::OsString> = std::env::args_os().skip(1).collect();
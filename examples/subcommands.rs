@@ -30,7 +30,7 @@ fn handle_remote_add(m: &rapp::Matches, c: &mut Ctx) {
     let _name = m.get_value("NAME");
 }
 
-fn main() {
+fn main() -> std::process::ExitCode {
     let env = rapp::Env { version: Some("2.0.0"), author: Some("Rust Args Parser"), ..Default::default() };
 
     let remote_add = rapp::CmdSpec::new("add")
@@ -69,19 +69,5 @@ fn main() {
 
     let root = rapp::CmdSpec::<'_, Ctx>::new("sc").help("Tool with nested subcommands").subcmd(remote);
 
-    let argv: Vec<OsString> = std::env::args_os().skip(1).collect();
-    let mut ctx = Ctx::default();
-    match rapp::parse(&env, &root, &argv, &mut ctx) {
-        Err(rapp::Error::ExitMsg { code, message }) => {
-            if let Some(msg) = message {
-                println!("{msg}");
-            }
-            std::process::exit(code);
-        }
-        Err(e) => {
-            eprintln!("{e}");
-            std::process::exit(2);
-        }
-        Ok(_) => {}
-    }
+    rapp::run(&env, &root, &mut Ctx::default())
 }
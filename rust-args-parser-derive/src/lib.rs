@@ -0,0 +1,592 @@
+//! Derive macros for [`rust-args-parser`](https://docs.rs/rust-args-parser).
+//!
+//! Use them through the `derive` feature of the main crate, which re-exports `Args` and
+//! `Subcommands` next to the runtime traits of the same name.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, GenericArgument, Ident, Lit,
+    LitChar, LitStr, PathArguments, Result, Type,
+};
+
+/// Derive `Args` for a struct with named fields.
+///
+/// Each field becomes an option (or positional, subcommand slot, flattened group); see the main
+/// crate's README for the supported `#[arg(...)]` and `#[command(...)]` attributes.
+#[proc_macro_derive(Args, attributes(arg, command))]
+pub fn derive_args(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_args(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Derive `Subcommands` for an enum of unit or single-field tuple variants.
+#[proc_macro_derive(Subcommands, attributes(command))]
+pub fn derive_subcommands(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_subcommands(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+// --- attributes ---
+
+/// `#[command(...)]` on a struct, enum variant or field.
+#[derive(Default)]
+struct CommandAttrs {
+    name: Option<LitStr>,
+    aliases: Vec<LitStr>,
+    subcommand: bool,
+    flatten: bool,
+}
+
+/// `short` (first letter of the long name) or `short = 'x'`.
+enum Short {
+    Auto,
+    Char(LitChar),
+}
+
+/// What a non-subcommand field turns into.
+#[derive(Default, PartialEq, Eq)]
+enum Kind {
+    /// Flag for `bool`, value option otherwise.
+    #[default]
+    Option,
+    /// `#[arg(count)]`: repeatable flag counting occurrences.
+    Count,
+    /// `#[arg(positional)]`.
+    Positional,
+}
+
+/// `#[arg(...)]` on a field.
+#[derive(Default)]
+struct ArgAttrs {
+    short: Option<Short>,
+    long: Option<LitStr>,
+    help: Option<LitStr>,
+    metavar: Option<LitStr>,
+    env: Option<LitStr>,
+    default: Option<LitStr>,
+    optional_value: Option<LitStr>,
+    choices: Vec<LitStr>,
+    kind: Kind,
+    global: bool,
+    negatable: bool,
+}
+
+fn command_attrs(attrs: &[Attribute]) -> Result<CommandAttrs> {
+    let mut out = CommandAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("command")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                out.name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("alias") {
+                out.aliases.push(meta.value()?.parse()?);
+            } else if meta.path.is_ident("subcommand") {
+                out.subcommand = true;
+            } else if meta.path.is_ident("flatten") {
+                out.flatten = true;
+            } else {
+                return Err(meta.error("unknown `command` attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(out)
+}
+
+fn arg_attrs(attrs: &[Attribute]) -> Result<ArgAttrs> {
+    let mut out = ArgAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("arg")) {
+        attr.parse_nested_meta(|meta| {
+            let p = &meta.path;
+            if p.is_ident("short") {
+                out.short = Some(if meta.input.peek(syn::Token![=]) {
+                    Short::Char(meta.value()?.parse()?)
+                } else {
+                    Short::Auto
+                });
+            } else if p.is_ident("long") {
+                out.long = Some(meta.value()?.parse()?);
+            } else if p.is_ident("help") {
+                out.help = Some(meta.value()?.parse()?);
+            } else if p.is_ident("metavar") {
+                out.metavar = Some(meta.value()?.parse()?);
+            } else if p.is_ident("env") {
+                out.env = Some(meta.value()?.parse()?);
+            } else if p.is_ident("default") {
+                out.default = Some(meta.value()?.parse()?);
+            } else if p.is_ident("optional_value") {
+                out.optional_value = Some(meta.value()?.parse()?);
+            } else if p.is_ident("choices") {
+                let list: syn::ExprArray = meta.value()?.parse()?;
+                for e in list.elems {
+                    match e {
+                        Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => out.choices.push(s),
+                        other => return Err(Error::new_spanned(other, "expected a string literal")),
+                    }
+                }
+            } else if p.is_ident("positional") {
+                out.kind = Kind::Positional;
+            } else if p.is_ident("count") {
+                out.kind = Kind::Count;
+            } else if p.is_ident("global") {
+                out.global = true;
+            } else if p.is_ident("negatable") {
+                out.negatable = true;
+            } else {
+                return Err(meta.error("unknown `arg` attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(out)
+}
+
+/// Doc comment lines without the space after `///`; indentation beyond it is kept.
+fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("doc")) {
+        if let Ok(nv) = attr.meta.require_name_value() {
+            if let Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) = &nv.value {
+                let line = s.value();
+                lines.push(line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string());
+            }
+        }
+    }
+    lines
+}
+
+/// Help text from doc comments: the first paragraph, its lines joined with spaces, and the whole comment
+/// (blank lines and indented code kept) when it has more paragraphs.
+fn doc_help(attrs: &[Attribute]) -> (Option<String>, Option<String>) {
+    let lines = doc_lines(attrs);
+    let mut paragraphs = lines.split(String::is_empty).filter(|p| !p.is_empty());
+    let Some(first) = paragraphs.next() else {
+        return (None, None);
+    };
+    let help = first.iter().map(|l| l.trim()).collect::<Vec<_>>().join(" ");
+    let long = paragraphs.next().is_some().then(|| lines.join("\n").trim_matches('\n').to_string());
+    (Some(help), long)
+}
+
+/// `.help(...)` from an explicit `help = "..."` or the doc comment, plus `.<long>(...)` (`long_help` or
+/// `long_about`) with the full doc comment when it has several paragraphs.
+fn help_builder(explicit: Option<&LitStr>, attrs: &[Attribute], long: &str) -> TokenStream2 {
+    if let Some(h) = explicit {
+        return quote!(.help(#h));
+    }
+    let (help, long_text) = doc_help(attrs);
+    let long = format_ident!("{long}");
+    let help = help.map(|h| quote!(.help(#h)));
+    let long_text = long_text.map(|t| quote!(.#long(#t)));
+    quote!(#help #long_text)
+}
+
+fn choices_builder(choices: &[LitStr]) -> TokenStream2 {
+    if choices.is_empty() {
+        TokenStream2::new()
+    } else {
+        quote!(.choices(&[#(#choices),*]))
+    }
+}
+
+// --- naming ---
+
+fn words(ident: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut prev_lower = false;
+    for ch in ident.trim_start_matches("r#").chars() {
+        if ch == '_' {
+            out.push(String::new());
+            prev_lower = false;
+            continue;
+        }
+        if ch.is_uppercase() && prev_lower {
+            out.push(String::new());
+        }
+        if out.is_empty() {
+            out.push(String::new());
+        }
+        if let Some(last) = out.last_mut() {
+            last.extend(ch.to_lowercase());
+        }
+        prev_lower = ch.is_lowercase() || ch.is_ascii_digit();
+    }
+    out.retain(|w| !w.is_empty());
+    out
+}
+
+fn kebab(ident: &Ident) -> String {
+    words(&ident.to_string()).join("-")
+}
+
+fn screaming(ident: &Ident) -> String {
+    words(&ident.to_string()).join("_").to_uppercase()
+}
+
+// --- types ---
+
+/// `Some(T)` if `ty` is `wrapper<T>` (matched on the last path segment).
+fn inner_type<'t>(ty: &'t Type, wrapper: &str) -> Option<&'t Type> {
+    let Type::Path(tp) = ty else { return None };
+    let seg = tp.path.segments.last()?;
+    if seg.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &seg.arguments else { return None };
+    match args.args.first()? {
+        GenericArgument::Type(t) if args.args.len() == 1 => Some(t),
+        _ => None,
+    }
+}
+
+fn is_bool(ty: &Type) -> bool {
+    matches!(ty, Type::Path(tp) if tp.qself.is_none() && tp.path.is_ident("bool"))
+}
+
+/// How parsed values land in a field.
+enum Slot<'t> {
+    /// `T`: overwrite.
+    Plain(&'t Type),
+    /// `Option<T>`: `Some(value)`.
+    Option(&'t Type),
+    /// `Vec<T>`: push every value.
+    Vec(&'t Type),
+}
+
+impl<'t> Slot<'t> {
+    fn of(ty: &'t Type) -> Self {
+        inner_type(ty, "Option")
+            .map(Slot::Option)
+            .or_else(|| inner_type(ty, "Vec").map(Slot::Vec))
+            .unwrap_or(Slot::Plain(ty))
+    }
+    const fn value_type(&self) -> &'t Type {
+        match self {
+            Self::Plain(t) | Self::Option(t) | Self::Vec(t) => t,
+        }
+    }
+    fn assign(&self, field: &Ident) -> TokenStream2 {
+        match self {
+            Self::Plain(_) => quote!(__s.#field = __x;),
+            Self::Option(_) => quote!(__s.#field = ::core::option::Option::Some(__x);),
+            Self::Vec(_) => quote!(__s.#field.push(__x);),
+        }
+    }
+}
+
+// --- #[derive(Args)] ---
+
+fn expand_args(input: &DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "`Args` cannot be derived for generic types"));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(ident, "`Args` can only be derived for structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(ident, "`Args` requires a struct with named fields"));
+    };
+
+    let cmd_attrs = command_attrs(&input.attrs)?;
+    let name = cmd_attrs.name.map_or_else(|| kebab(ident), |n| n.value());
+    let help = help_builder(None, &input.attrs, "long_about");
+    let aliases = cmd_attrs.aliases.iter().map(|a| quote!(.alias(#a)));
+
+    check_shorts(&fields.named)?;
+    let mut steps = Vec::new();
+    for field in &fields.named {
+        steps.push(expand_field(field)?);
+    }
+
+    Ok(quote! {
+        impl ::rust_args_parser::Args for #ident {
+            fn command() -> ::rust_args_parser::CmdSpec<'static, Self> {
+                let __cmd = ::rust_args_parser::CmdSpec::new(#name) #help #(#aliases)*;
+                let __cmd = <Self as ::rust_args_parser::Args>::augment_args(
+                    __cmd,
+                    ::rust_args_parser::project(|__s: &mut Self| __s),
+                );
+                ::rust_args_parser::check_shorts(&__cmd);
+                __cmd
+            }
+
+            fn augment_args<__P: 'static>(
+                __cmd: ::rust_args_parser::CmdSpec<'static, __P>,
+                __project: ::rust_args_parser::Project<__P, Self>,
+            ) -> ::rust_args_parser::CmdSpec<'static, __P> {
+                #(#steps)*
+                __cmd
+            }
+        }
+    })
+}
+
+/// One `let __cmd = ...;` step adding the field to the spec.
+fn expand_field(field: &syn::Field) -> Result<TokenStream2> {
+    let Some(ident) = field.ident.as_ref() else {
+        return Err(Error::new_spanned(field, "expected a named field"));
+    };
+    let ty = &field.ty;
+    let cmd = command_attrs(&field.attrs)?;
+
+    if cmd.subcommand {
+        let Some(inner) = inner_type(ty, "Option") else {
+            return Err(Error::new_spanned(ty, "`#[command(subcommand)]` fields must be `Option<Enum>`"));
+        };
+        return Ok(quote! {
+            let __cmd = {
+                let __outer = ::std::rc::Rc::clone(&__project);
+                <#inner as ::rust_args_parser::Subcommands>::augment_subcommands(
+                    __cmd,
+                    ::rust_args_parser::project(move |__c: &mut __P| &mut __outer(__c).#ident),
+                )
+            };
+        });
+    }
+    if cmd.flatten {
+        return Ok(quote! {
+            let __cmd = {
+                let __outer = ::std::rc::Rc::clone(&__project);
+                <#ty as ::rust_args_parser::Args>::augment_args(
+                    __cmd,
+                    ::rust_args_parser::project(move |__c: &mut __P| &mut __outer(__c).#ident),
+                )
+            };
+        });
+    }
+
+    let arg = arg_attrs(&field.attrs)?;
+    if arg.kind == Kind::Positional {
+        expand_positional(ident, ty, &arg, &field.attrs)
+    } else {
+        Ok(expand_option(ident, ty, &arg, &field.attrs))
+    }
+}
+
+fn option_long(ident: &Ident, arg: &ArgAttrs) -> String {
+    arg.long.as_ref().map_or_else(|| kebab(ident), LitStr::value)
+}
+
+/// `short = 'x'`, or the first letter of the long name for a bare `short`.
+fn option_short(ident: &Ident, arg: &ArgAttrs) -> Option<char> {
+    match &arg.short {
+        Some(Short::Char(c)) => Some(c.value()),
+        Some(Short::Auto) => option_long(ident, arg).chars().next(),
+        None => None,
+    }
+}
+
+/// Reject short options that repeat a sibling's or shadow a built-in (`-h`, `-V`, `-A`), which
+/// would leave the field unreachable. Clashes through flattened structs and inherited globals are
+/// caught when `command()` builds the spec.
+fn check_shorts<'f>(fields: impl IntoIterator<Item = &'f syn::Field>) -> Result<()> {
+    let mut seen: Vec<(char, &Ident)> = Vec::new();
+    for field in fields {
+        let Some(ident) = field.ident.as_ref() else { continue };
+        let cmd = command_attrs(&field.attrs)?;
+        let arg = arg_attrs(&field.attrs)?;
+        if cmd.subcommand || cmd.flatten || arg.kind == Kind::Positional {
+            continue;
+        }
+        let Some(c) = option_short(ident, &arg) else { continue };
+        let builtin = match c {
+            'h' => Some("--help"),
+            'V' => Some("--version"),
+            'A' => Some("--author"),
+            _ => None,
+        };
+        if let Some(b) = builtin {
+            let msg = format!("short option '-{c}' of `{ident}` is taken by the built-in {b}; set `short = '...'`");
+            return Err(Error::new_spanned(ident, msg));
+        }
+        if let Some((_, other)) = seen.iter().find(|(s, _)| *s == c) {
+            let msg = format!("short option '-{c}' of `{ident}` is already used by `{other}`; set `short = '...'`");
+            return Err(Error::new_spanned(ident, msg));
+        }
+        seen.push((c, ident));
+    }
+    Ok(())
+}
+
+fn expand_option(ident: &Ident, ty: &Type, arg: &ArgAttrs, attrs: &[Attribute]) -> TokenStream2 {
+    let key = ident.to_string().trim_start_matches("r#").to_string();
+    let long = option_long(ident, arg);
+    let short = option_short(ident, arg);
+
+    let mut builders = vec![quote!(.long(#long))];
+    if let Some(c) = short {
+        builders.push(quote!(.short(#c)));
+    }
+    builders.push(help_builder(arg.help.as_ref(), attrs, "long_help"));
+    if let Some(e) = &arg.env {
+        builders.push(quote!(.env(#e)));
+    }
+    if let Some(d) = &arg.default {
+        builders.push(quote!(.default(#d)));
+    }
+    if let Some(m) = &arg.optional_value {
+        builders.push(quote!(.optional_value(#m)));
+    }
+    if arg.global {
+        builders.push(quote!(.global()));
+    }
+    builders.push(choices_builder(&arg.choices));
+    if arg.negatable {
+        builders.push(quote!(.negatable()));
+    }
+
+    let spec = if arg.kind == Kind::Count {
+        builders.push(quote!(.repeatable().count_from_env()));
+        quote! {
+            ::rust_args_parser::OptSpec::<'static, __P>::flag(#key, move |__c: &mut __P| {
+                let __s = __p(__c);
+                __s.#ident = __s.#ident.saturating_add(1);
+            })
+        }
+    } else if is_bool(ty) {
+        quote! {
+            ::rust_args_parser::OptSpec::<'static, __P>::flag(#key, move |__c: &mut __P| {
+                __p(__c).#ident = true;
+            })
+        }
+    } else {
+        let slot = Slot::of(ty);
+        match slot {
+            Slot::Plain(_) if arg.default.is_none() => builders.push(quote!(.required())),
+            Slot::Vec(_) => builders.push(quote!(.repeatable())),
+            _ => {}
+        }
+        let metavar = arg.metavar.as_ref().map_or_else(|| screaming(ident), LitStr::value);
+        builders.push(quote!(.metavar(#metavar)));
+        let label = format!("--{long}");
+        let vt = slot.value_type();
+        let assign = slot.assign(ident);
+        quote! {
+            ::rust_args_parser::OptSpec::<'static, __P>::value(#key, move |__v: &::std::ffi::OsStr, __c: &mut __P| {
+                if let ::core::option::Option::Some(__x) = ::rust_args_parser::parse_value::<#vt>(__v) {
+                    let __s = __p(__c);
+                    #assign
+                }
+            })
+            .validator(|__v: &::std::ffi::OsStr| ::rust_args_parser::check_value::<#vt>(#label, __v))
+        }
+    };
+
+    quote! {
+        let __cmd = {
+            let __p = ::std::rc::Rc::clone(&__project);
+            __cmd.opt(#spec #(#builders)*)
+        };
+    }
+}
+
+fn expand_positional(ident: &Ident, ty: &Type, arg: &ArgAttrs, attrs: &[Attribute]) -> Result<TokenStream2> {
+    if arg.short.is_some()
+        || arg.long.is_some()
+        || arg.env.is_some()
+        || arg.default.is_some()
+        || arg.optional_value.is_some()
+        || arg.negatable
+    {
+        return Err(Error::new_spanned(ident, "positionals only accept `help`, `metavar` and `choices`"));
+    }
+    let name = arg.metavar.as_ref().map_or_else(|| screaming(ident), LitStr::value);
+    let slot = Slot::of(ty);
+    let card = match slot {
+        Slot::Plain(_) => quote!(.required()),
+        Slot::Option(_) => TokenStream2::new(),
+        Slot::Vec(_) => quote!(.many()),
+    };
+    let help = help_builder(arg.help.as_ref(), attrs, "long_help");
+    let choices = choices_builder(&arg.choices);
+    let label = format!("<{name}>");
+    let vt = slot.value_type();
+    let assign = slot.assign(ident);
+    Ok(quote! {
+        let __cmd = {
+            let __p = ::std::rc::Rc::clone(&__project);
+            __cmd.pos(
+                ::rust_args_parser::PosSpec::<'static, __P>::new(#name, move |__v: &::std::ffi::OsStr, __c: &mut __P| {
+                    if let ::core::option::Option::Some(__x) = ::rust_args_parser::parse_value::<#vt>(__v) {
+                        let __s = __p(__c);
+                        #assign
+                    }
+                })
+                .validator(|__v: &::std::ffi::OsStr| ::rust_args_parser::check_value::<#vt>(#label, __v))
+                #card #help #choices
+            )
+        };
+    })
+}
+
+// --- #[derive(Subcommands)] ---
+
+fn expand_subcommands(input: &DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "`Subcommands` cannot be derived for generic types"));
+    }
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(ident, "`Subcommands` can only be derived for enums"));
+    };
+
+    let mut steps = Vec::new();
+    for variant in &data.variants {
+        let v = &variant.ident;
+        let attrs = command_attrs(&variant.attrs)?;
+        let name = attrs.name.map_or_else(|| kebab(v), |n| n.value());
+        let help = help_builder(None, &variant.attrs, "long_about");
+        let aliases = attrs.aliases.iter().map(|a| quote!(.alias(#a)));
+        let sub = quote!(::rust_args_parser::CmdSpec::<'static, __P>::new(#name) #help #(#aliases)*);
+
+        let step = match &variant.fields {
+            Fields::Unit => quote! {
+                let __cmd = {
+                    let __outer = ::std::rc::Rc::clone(&__project);
+                    __cmd.subcmd(#sub.handler(move |_: &::rust_args_parser::Matches, __c: &mut __P| {
+                        *__outer(__c) = ::core::option::Option::Some(Self::#v);
+                    }))
+                };
+            },
+            Fields::Unnamed(f) if f.unnamed.len() == 1 => {
+                let ty = &f.unnamed[0].ty;
+                quote! {
+                    let __cmd = {
+                        let __outer = ::std::rc::Rc::clone(&__project);
+                        let __inner: ::rust_args_parser::Project<__P, #ty> =
+                            ::rust_args_parser::project(move |__c: &mut __P| {
+                                let __slot = __outer(__c);
+                                if !::core::matches!(__slot, ::core::option::Option::Some(Self::#v(_))) {
+                                    *__slot = ::core::option::Option::Some(Self::#v(::core::default::Default::default()));
+                                }
+                                match __slot {
+                                    ::core::option::Option::Some(Self::#v(__x)) => __x,
+                                    _ => ::core::unreachable!(),
+                                }
+                            });
+                        let __sub = <#ty as ::rust_args_parser::Args>::augment_args(#sub, ::std::rc::Rc::clone(&__inner));
+                        __cmd.subcmd(__sub.handler(move |_: &::rust_args_parser::Matches, __c: &mut __P| {
+                            __inner(__c);
+                        }))
+                    };
+                }
+            }
+            _ => return Err(Error::new_spanned(v, "`Subcommands` variants must be unit or wrap a single `Args` type")),
+        };
+        steps.push(step);
+    }
+
+    Ok(quote! {
+        impl ::rust_args_parser::Subcommands for #ident {
+            fn augment_subcommands<__P: 'static>(
+                __cmd: ::rust_args_parser::CmdSpec<'static, __P>,
+                __project: ::rust_args_parser::Project<__P, ::core::option::Option<Self>>,
+            ) -> ::rust_args_parser::CmdSpec<'static, __P> {
+                #(#steps)*
+                __cmd
+            }
+        }
+    })
+}
@@ -0,0 +1,664 @@
+use crate::{CmdSpec, Env, Error, OptSpec, PosSpec, Repeat};
+use core::fmt::Write;
+
+/// Target shell for static completion scripts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl core::str::FromStr for Shell {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            "powershell" | "pwsh" => Ok(Self::PowerShell),
+            _ => Err(Error::User(format!("unknown shell: '{s}' (expected bash, zsh, fish or powershell)"))),
+        }
+    }
+}
+
+/// Render a static completion script for `bin` that knows every subcommand, alias, option and positional
+/// of `root`. Built-ins (`--help`, `--version`, `--author`) are included according to `env`.
+#[must_use]
+pub fn render_completions<Ctx: ?Sized>(env: &Env, root: &CmdSpec<'_, Ctx>, shell: Shell, bin: &str) -> String {
+    let nodes = collect_nodes(env, root, bin);
+    match shell {
+        Shell::Bash => render_bash(bin, &nodes),
+        Shell::Zsh => render_zsh(bin, &nodes),
+        Shell::Fish => render_fish(bin, &nodes),
+        Shell::PowerShell => render_powershell(bin, &nodes),
+    }
+}
+
+// ---------- flattened command tree ----------
+
+#[allow(clippy::struct_excessive_bools)]
+struct CompOpt<'s> {
+    short: Option<char>,
+    long: Option<&'s str>,
+    metavar: Option<&'s str>,
+    help: Option<&'s str>,
+    takes_value: bool,
+    optional_value: bool,
+    repeatable: bool,
+    negatable: bool,
+    choices: &'s [&'s str],
+}
+
+impl<'s> CompOpt<'s> {
+    fn from_spec<Ctx: ?Sized>(o: &'s OptSpec<'_, Ctx>) -> Self {
+        Self {
+            short: o.get_short(),
+            long: o.get_long(),
+            metavar: o.get_metavar(),
+            help: o.get_help(),
+            takes_value: o.is_value() && !o.is_optional_value(),
+            optional_value: o.is_optional_value(),
+            repeatable: !o.is_value() || o.get_repeat() == Repeat::Many,
+            negatable: o.is_negatable(),
+            choices: o.get_choices(),
+        }
+    }
+    const fn builtin(short: char, long: &'static str, help: &'static str) -> Self {
+        Self {
+            short: Some(short),
+            long: Some(long),
+            metavar: None,
+            help: Some(help),
+            takes_value: false,
+            optional_value: false,
+            repeatable: false,
+            negatable: false,
+            choices: &[],
+        }
+    }
+    /// All spellings (`-j`, `--jobs`, `--no-color`).
+    fn spellings(&self) -> Vec<String> {
+        let mut v = Vec::new();
+        if let Some(s) = self.short {
+            v.push(format!("-{s}"));
+        }
+        if let Some(l) = self.long {
+            v.push(format!("--{l}"));
+        }
+        if let Some(l) = self.negated() {
+            v.push(format!("--{l}"));
+        }
+        v
+    }
+    /// `no-<long>` for negatable flags.
+    fn negated(&self) -> Option<String> {
+        self.long.filter(|_| self.negatable).map(|l| format!("no-{l}"))
+    }
+}
+
+struct CompPos<'s> {
+    name: &'s str,
+    help: Option<&'s str>,
+    multiple: bool,
+    choices: &'s [&'s str],
+}
+
+impl<'s> CompPos<'s> {
+    fn from_spec<Ctx: ?Sized>(p: &'s PosSpec<'_, Ctx>) -> Self {
+        Self { name: p.get_name(), help: p.get_help(), multiple: p.is_multiple(), choices: p.get_choices() }
+    }
+}
+
+struct CompSub<'s> {
+    id: String,
+    names: Vec<&'s str>,
+    help: Option<&'s str>,
+}
+
+struct CompNode<'s> {
+    /// Shell-safe identifier of the command path, e.g. `git__remote__add`.
+    id: String,
+    subs: Vec<CompSub<'s>>,
+    opts: Vec<CompOpt<'s>>,
+    positionals: Vec<CompPos<'s>>,
+}
+
+fn ident(s: &str) -> String {
+    s.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
+
+fn collect_nodes<'s, Ctx: ?Sized>(env: &Env, root: &'s CmdSpec<'_, Ctx>, bin: &str) -> Vec<CompNode<'s>> {
+    let mut out = Vec::new();
+    let mut inherited = Vec::new();
+    collect_rec(env, root, &ident(bin), true, &mut inherited, &mut out);
+    out
+}
+
+fn collect_rec<'s, 'a: 's, Ctx: ?Sized>(
+    env: &Env,
+    cmd: &'s CmdSpec<'a, Ctx>,
+    id: &str,
+    is_root: bool,
+    inherited: &mut Vec<&'s OptSpec<'a, Ctx>>,
+    out: &mut Vec<CompNode<'s>>,
+) {
+    let mut opts = Vec::new();
+    if env.auto_help {
+        opts.push(CompOpt::builtin('h', "help", "Show this help and exit"));
+    }
+    if is_root {
+        if env.version.is_some() {
+            opts.push(CompOpt::builtin('V', "version", "Show version and exit"));
+        }
+        if env.author.is_some() {
+            opts.push(CompOpt::builtin('A', "author", "Show author and exit"));
+        }
+    }
+    opts.extend(cmd.get_opts().iter().map(CompOpt::from_spec));
+    opts.extend(inherited.iter().map(|o| CompOpt::from_spec(*o)));
+
+    let subs = cmd
+        .get_subcommands()
+        .iter()
+        .map(|sc| {
+            let mut names = vec![sc.get_name()];
+            names.extend(sc.get_aliases().iter().copied());
+            CompSub { id: format!("{id}__{}", ident(sc.get_name())), names, help: sc.get_help() }
+        })
+        .collect::<Vec<_>>();
+    let positionals = cmd.get_positionals().iter().map(CompPos::from_spec).collect();
+    out.push(CompNode { id: id.to_string(), subs, opts, positionals });
+
+    let depth = inherited.len();
+    inherited.extend(cmd.get_opts().iter().filter(|o| o.is_global()));
+    for sc in cmd.get_subcommands() {
+        collect_rec(env, sc, &format!("{id}__{}", ident(sc.get_name())), false, inherited, out);
+    }
+    inherited.truncate(depth);
+}
+
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ---------- bash ----------
+
+fn render_bash(bin: &str, nodes: &[CompNode<'_>]) -> String {
+    let f = format!("_{}", ident(bin));
+    let mut out = String::new();
+    let _ = writeln!(out, "# bash completion for {bin}");
+    let _ = writeln!(out, "{f}() {{");
+    let _ = writeln!(out, "    local cur prev cmd i w");
+    let _ = writeln!(out, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"");
+    let _ = writeln!(out, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
+    let _ = writeln!(out, "    cmd=\"{}\"", nodes[0].id);
+    let _ = writeln!(out, "    for ((i = 1; i < COMP_CWORD; i++)); do");
+    let _ = writeln!(out, "        w=\"${{COMP_WORDS[i]}}\"");
+    let _ = writeln!(out, "        case \"${{cmd}},${{w}}\" in");
+    for n in nodes {
+        for s in &n.subs {
+            let pats: Vec<String> = s.names.iter().map(|a| format!("{},{a}", n.id)).collect();
+            let _ = writeln!(out, "            {}) cmd=\"{}\" ;;", pats.join("|"), s.id);
+        }
+        let pats: Vec<String> = n
+            .opts
+            .iter()
+            .filter(|o| o.takes_value)
+            .flat_map(CompOpt::spellings)
+            .map(|sp| format!("{},{sp}", n.id))
+            .collect();
+        if !pats.is_empty() {
+            let _ = writeln!(out, "            {}) ((i++)) ;;", pats.join("|"));
+        }
+    }
+    let _ = writeln!(out, "        esac");
+    let _ = writeln!(out, "    done");
+    let _ = writeln!(out, "    case \"${{cmd}}\" in");
+    for n in nodes {
+        let _ = writeln!(out, "        {})", n.id);
+        let value_opts: Vec<String> =
+            n.opts.iter().filter(|o| o.takes_value && o.choices.is_empty()).flat_map(CompOpt::spellings).collect();
+        let choice_opts: Vec<&CompOpt<'_>> = n.opts.iter().filter(|o| !o.choices.is_empty()).collect();
+        if !value_opts.is_empty() || !choice_opts.is_empty() {
+            let _ = writeln!(out, "            case \"${{prev}}\" in");
+            for o in choice_opts {
+                let _ = writeln!(out, "                {})", o.spellings().join("|"));
+                let _ = writeln!(
+                    out,
+                    "                    COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))",
+                    o.choices.join(" ")
+                );
+                let _ = writeln!(out, "                    return 0 ;;");
+            }
+            if !value_opts.is_empty() {
+                let _ = writeln!(out, "                {})", value_opts.join("|"));
+                let _ = writeln!(out, "                    COMPREPLY=($(compgen -f -- \"${{cur}}\"))");
+                let _ = writeln!(out, "                    return 0 ;;");
+            }
+            let _ = writeln!(out, "            esac");
+        }
+        let opts: Vec<String> = n.opts.iter().flat_map(CompOpt::spellings).collect();
+        let mut subs: Vec<&str> = n.subs.iter().flat_map(|s| s.names.iter().copied()).collect();
+        subs.extend(n.positionals.iter().flat_map(|p| p.choices.iter().copied()));
+        let pos_files = n.positionals.iter().any(|p| p.choices.is_empty());
+        let _ = writeln!(out, "            if [[ \"${{cur}}\" == -* ]]; then");
+        let _ = writeln!(out, "                COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))", opts.join(" "));
+        if pos_files && subs.is_empty() {
+            let _ = writeln!(out, "            else");
+            let _ = writeln!(out, "                COMPREPLY=($(compgen -f -- \"${{cur}}\"))");
+        } else if pos_files {
+            let _ = writeln!(out, "            else");
+            let _ = writeln!(
+                out,
+                "                COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\") $(compgen -f -- \"${{cur}}\"))",
+                subs.join(" ")
+            );
+        } else if !subs.is_empty() {
+            let _ = writeln!(out, "            else");
+            let _ = writeln!(out, "                COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))", subs.join(" "));
+        }
+        let _ = writeln!(out, "            fi");
+        let _ = writeln!(out, "            ;;");
+    }
+    let _ = writeln!(out, "    esac");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out, "complete -F {f} -o bashdefault -o default {bin}");
+    out
+}
+
+// ---------- zsh ----------
+
+fn zsh_quote(s: &str) -> String {
+    s.replace('\'', "'\\''")
+}
+
+fn zsh_help(s: Option<&str>) -> String {
+    zsh_quote(&one_line(s.unwrap_or("")).replace('[', "\\[").replace(']', "\\]"))
+}
+
+/// `_files`, or `(a b c)` for a fixed set of choices.
+fn zsh_action(choices: &[&str]) -> String {
+    if choices.is_empty() {
+        "_files".to_string()
+    } else {
+        let words: Vec<String> = choices.iter().map(|c| zsh_quote(&c.replace(' ', "\\ "))).collect();
+        format!("({})", words.join(" "))
+    }
+}
+
+fn render_zsh(bin: &str, nodes: &[CompNode<'_>]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "#compdef {bin}");
+    for n in nodes {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "_{}() {{", n.id);
+        let _ = writeln!(out, "    local curcontext=\"$curcontext\" state line");
+        let _ = writeln!(out, "    typeset -A opt_args");
+        let _ = writeln!(out, "    _arguments -C \\");
+        for o in &n.opts {
+            let all = o.spellings();
+            let excl = if o.repeatable { "*".to_string() } else { format!("({})", all.join(" ")) };
+            let help = zsh_help(o.help);
+            let value = if o.takes_value || o.optional_value {
+                let colons = if o.optional_value { "::" } else { ":" };
+                let metavar = zsh_quote(&o.metavar.unwrap_or("VALUE").replace(':', "\\:"));
+                format!("{colons}{metavar}:{}", zsh_action(o.choices))
+            } else {
+                String::new()
+            };
+            if let Some(s) = o.short {
+                let plus = if o.takes_value {
+                    "+"
+                } else if o.optional_value {
+                    "-"
+                } else {
+                    ""
+                };
+                let _ = writeln!(out, "        '{excl}-{s}{plus}[{help}]{value}' \\");
+            }
+            if let Some(l) = o.long {
+                let eq = if o.takes_value {
+                    "="
+                } else if o.optional_value {
+                    "=-"
+                } else {
+                    ""
+                };
+                let _ = writeln!(out, "        '{excl}--{l}{eq}[{help}]{value}' \\");
+            }
+            if let Some(l) = o.negated() {
+                let _ = writeln!(out, "        '{excl}--{l}[{help}]' \\");
+            }
+        }
+        if n.subs.is_empty() {
+            for (i, p) in n.positionals.iter().enumerate() {
+                let name = zsh_quote(&p.name.replace(':', "\\:"));
+                let action = zsh_action(p.choices);
+                if p.multiple {
+                    let _ = writeln!(out, "        '*:{name}:{action}' \\");
+                } else {
+                    let _ = writeln!(out, "        '{}:{name}:{action}' \\", i + 1);
+                }
+            }
+            let _ = writeln!(out, "        && return 0");
+        } else {
+            let _ = writeln!(out, "        '1: :_{}_commands' \\", n.id);
+            let _ = writeln!(out, "        '*::arg:->args' \\");
+            let _ = writeln!(out, "        && return 0");
+            let _ = writeln!(out, "    case $state in");
+            let _ = writeln!(out, "        args)");
+            let _ = writeln!(out, "            case $line[1] in");
+            for s in &n.subs {
+                let _ = writeln!(out, "                {}) _{} ;;", s.names.join("|"), s.id);
+            }
+            let _ = writeln!(out, "            esac");
+            let _ = writeln!(out, "            ;;");
+            let _ = writeln!(out, "    esac");
+        }
+        let _ = writeln!(out, "}}");
+        if !n.subs.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "_{}_commands() {{", n.id);
+            let _ = writeln!(out, "    local commands; commands=(");
+            for s in &n.subs {
+                let help = zsh_quote(&one_line(s.help.unwrap_or("")));
+                for name in &s.names {
+                    let _ = writeln!(out, "        '{}:{help}'", zsh_quote(&name.replace(':', "\\:")));
+                }
+            }
+            let _ = writeln!(out, "    )");
+            let _ = writeln!(out, "    _describe -t commands 'commands' commands");
+            let _ = writeln!(out, "}}");
+        }
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "if [ \"$funcstack[1]\" = \"_{0}\" ]; then", nodes[0].id);
+    let _ = writeln!(out, "    _{} \"$@\"", nodes[0].id);
+    let _ = writeln!(out, "else");
+    let _ = writeln!(out, "    compdef _{} {bin}", nodes[0].id);
+    let _ = writeln!(out, "fi");
+    out
+}
+
+// ---------- fish ----------
+
+fn fish_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn render_fish(bin: &str, nodes: &[CompNode<'_>]) -> String {
+    let id = &nodes[0].id;
+    let mut out = String::new();
+    let _ = writeln!(out, "# fish completion for {bin}");
+    let _ = writeln!(out, "function __{id}_cmd_path");
+    let _ = writeln!(out, "    set -l tokens (commandline -opc)");
+    let _ = writeln!(out, "    set -l path {id}");
+    let _ = writeln!(out, "    set -l skip 0");
+    let _ = writeln!(out, "    for t in $tokens[2..-1]");
+    let _ = writeln!(out, "        if test $skip = 1");
+    let _ = writeln!(out, "            set skip 0");
+    let _ = writeln!(out, "            continue");
+    let _ = writeln!(out, "        end");
+    let _ = writeln!(out, "        switch \"$path,$t\"");
+    for n in nodes {
+        for s in &n.subs {
+            let pats: Vec<String> = s.names.iter().map(|a| fish_quote(&format!("{},{a}", n.id))).collect();
+            let _ = writeln!(out, "            case {}", pats.join(" "));
+            let _ = writeln!(out, "                set path {}", s.id);
+        }
+        let pats: Vec<String> = n
+            .opts
+            .iter()
+            .filter(|o| o.takes_value)
+            .flat_map(CompOpt::spellings)
+            .map(|sp| fish_quote(&format!("{},{sp}", n.id)))
+            .collect();
+        if !pats.is_empty() {
+            let _ = writeln!(out, "            case {}", pats.join(" "));
+            let _ = writeln!(out, "                set skip 1");
+        }
+    }
+    let _ = writeln!(out, "        end");
+    let _ = writeln!(out, "    end");
+    let _ = writeln!(out, "    echo $path");
+    let _ = writeln!(out, "end");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "function __{id}_at");
+    let _ = writeln!(out, "    test (__{id}_cmd_path) = $argv[1]");
+    let _ = writeln!(out, "end");
+    for n in nodes {
+        let _ = writeln!(out);
+        let cond = format!("-n '__{id}_at {}'", n.id);
+        for s in &n.subs {
+            for name in &s.names {
+                let mut line = format!("complete -c {bin} {cond} -f -a {}", fish_quote(name));
+                if let Some(h) = s.help {
+                    let _ = write!(line, " -d {}", fish_quote(&one_line(h)));
+                }
+                let _ = writeln!(out, "{line}");
+            }
+        }
+        for o in &n.opts {
+            let mut line = format!("complete -c {bin} {cond}");
+            if let Some(s) = o.short {
+                let _ = write!(line, " -s {s}");
+            }
+            if let Some(l) = o.long {
+                let _ = write!(line, " -l {l}");
+            }
+            if o.takes_value {
+                line.push_str(" -r");
+            }
+            if !o.choices.is_empty() {
+                let _ = write!(line, " -f -a {}", fish_quote(&o.choices.join(" ")));
+            }
+            if let Some(h) = o.help {
+                let _ = write!(line, " -d {}", fish_quote(&one_line(h)));
+            }
+            let _ = writeln!(out, "{line}");
+            if let Some(l) = o.negated() {
+                let _ = writeln!(out, "complete -c {bin} {cond} -l {l}");
+            }
+        }
+        for p in &n.positionals {
+            let mut line = if p.choices.is_empty() {
+                format!("complete -c {bin} {cond} -F")
+            } else {
+                format!("complete -c {bin} {cond} -f -a {}", fish_quote(&p.choices.join(" ")))
+            };
+            let _ = write!(line, " -d {}", fish_quote(&one_line(p.help.unwrap_or(p.name))));
+            let _ = writeln!(out, "{line}");
+        }
+    }
+    out
+}
+
+// ---------- PowerShell ----------
+
+fn ps_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn render_powershell(bin: &str, nodes: &[CompNode<'_>]) -> String {
+    let has_choices = nodes.iter().flat_map(|n| &n.opts).any(|o| !o.choices.is_empty());
+    let mut out = String::new();
+    let _ = writeln!(out, "using namespace System.Management.Automation");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{", ps_quote(bin));
+    let _ = writeln!(out, "    param($wordToComplete, $commandAst, $cursorPosition)");
+    let _ = writeln!(out, "    $path = {}", ps_quote(&nodes[0].id));
+    let _ = writeln!(out, "    $skip = $false");
+    let _ = writeln!(out, "    foreach ($el in $commandAst.CommandElements | Select-Object -Skip 1) {{");
+    let _ = writeln!(out, "        if ($el.Extent.EndOffset -ge $cursorPosition) {{ break }}");
+    let _ = writeln!(out, "        $t = $el.ToString()");
+    let _ = writeln!(out, "        if ($skip) {{ $skip = $false; continue }}");
+    let _ = writeln!(out, "        switch -CaseSensitive (\"$path,$t\") {{");
+    for n in nodes {
+        for s in &n.subs {
+            for name in &s.names {
+                let _ = writeln!(
+                    out,
+                    "            {} {{ $path = {}; break }}",
+                    ps_quote(&format!("{},{name}", n.id)),
+                    ps_quote(&s.id)
+                );
+            }
+        }
+        for sp in n.opts.iter().filter(|o| o.takes_value).flat_map(CompOpt::spellings) {
+            let set_opt = if has_choices { " $opt = $t;" } else { "" };
+            let _ = writeln!(
+                out,
+                "            {} {{ $skip = $true;{set_opt} break }}",
+                ps_quote(&format!("{},{sp}", n.id))
+            );
+        }
+    }
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }}");
+    if has_choices {
+        render_powershell_choices(&mut out, nodes);
+    }
+    let _ = writeln!(out, "    $candidates = switch ($path) {{");
+    for n in nodes {
+        let _ = writeln!(out, "        {} {{", ps_quote(&n.id));
+        for s in &n.subs {
+            for name in &s.names {
+                let tip = one_line(s.help.unwrap_or(name));
+                let _ = writeln!(
+                    out,
+                    "            [CompletionResult]::new({0}, {0}, [CompletionResultType]::ParameterValue, {1})",
+                    ps_quote(name),
+                    ps_quote(&tip)
+                );
+            }
+        }
+        for c in n.positionals.iter().flat_map(|p| p.choices) {
+            let _ = writeln!(
+                out,
+                "            [CompletionResult]::new({0}, {0}, [CompletionResultType]::ParameterValue, {0})",
+                ps_quote(c)
+            );
+        }
+        for o in &n.opts {
+            for sp in o.spellings() {
+                let tip = one_line(o.help.unwrap_or(&sp));
+                let _ = writeln!(
+                    out,
+                    "            [CompletionResult]::new({0}, {0}, [CompletionResultType]::ParameterName, {1})",
+                    ps_quote(&sp),
+                    ps_quote(&tip)
+                );
+            }
+        }
+        let _ = writeln!(out, "        }}");
+    }
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "    $candidates | Where-Object {{ $_.CompletionText -like \"$wordToComplete*\" }}");
+    let _ = writeln!(out, "}}");
+    out
+}
+
+/// Value completion for options with `choices`: runs when the cursor is on the value of `$opt`.
+fn render_powershell_choices(out: &mut String, nodes: &[CompNode<'_>]) {
+    let _ = writeln!(out, "    if ($skip) {{");
+    let _ = writeln!(out, "        $values = switch -CaseSensitive (\"$path,$opt\") {{");
+    for n in nodes {
+        for o in n.opts.iter().filter(|o| !o.choices.is_empty()) {
+            let values: Vec<String> = o.choices.iter().map(|c| ps_quote(c)).collect();
+            for sp in o.spellings() {
+                let _ =
+                    writeln!(out, "            {} {{ {} }}", ps_quote(&format!("{},{sp}", n.id)), values.join(", "));
+            }
+        }
+    }
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "        $values | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{");
+    let _ = writeln!(out, "            [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_)");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "        return");
+    let _ = writeln!(out, "    }}");
+}
+
+/// Render shell glue that delegates completion to the binary itself via the hidden
+/// [`COMPLETE_COMMAND`](crate::COMPLETE_COMMAND) protocol, so `completer` callbacks run at completion time.
+///
+/// The binary must parse with `Env::dynamic_completions` set.
+#[must_use]
+pub fn render_dynamic_completions(shell: Shell, bin: &str) -> String {
+    let id = ident(bin);
+    let cc = crate::COMPLETE_COMMAND;
+    let mut out = String::new();
+    match shell {
+        Shell::Bash => {
+            let _ = writeln!(out, "# bash dynamic completion for {bin}");
+            let _ = writeln!(out, "_{id}_dynamic() {{");
+            let _ = writeln!(out, "    local IFS=$'\\n' line cur prefix");
+            let _ = writeln!(out, "    local -a words");
+            let _ = writeln!(out, "    # split on blanks only: COMP_WORDS also breaks `--opt=value` at `=` and `:`");
+            let _ = writeln!(out, "    IFS=$' \\t\\n' read -ra words <<< \"${{COMP_LINE:0:COMP_POINT}}\"");
+            let _ = writeln!(out, "    [[ \"${{COMP_LINE:COMP_POINT-1:1}}\" == [[:blank:]] ]] && words+=(\"\")");
+            let _ = writeln!(out, "    cur=\"${{words[${{#words[@]}}-1]}}\"");
+            let _ = writeln!(out, "    # readline replaces only the text after the last `=` or `:` of the word");
+            let _ = writeln!(out, "    prefix=\"${{cur%\"${{cur##*[=:]}}\"}}\"");
+            let _ = writeln!(out, "    COMPREPLY=()");
+            let _ = writeln!(
+                out,
+                "    for line in $({bin} {cc} \"$((${{#words[@]}} - 2))\" \"${{words[@]:1}}\" 2>/dev/null); do"
+            );
+            let _ = writeln!(out, "        line=\"${{line%%$'\\t'*}}\"");
+            let _ = writeln!(out, "        COMPREPLY+=(\"${{line#\"$prefix\"}}\")");
+            let _ = writeln!(out, "    done");
+            let _ = writeln!(out, "}}");
+            let _ = writeln!(out, "complete -o bashdefault -o default -F _{id}_dynamic {bin}");
+        }
+        Shell::Zsh => {
+            let _ = writeln!(out, "#compdef {bin}");
+            let _ = writeln!(out);
+            let _ = writeln!(out, "_{id}() {{");
+            let _ = writeln!(out, "    local -a cands");
+            let _ = writeln!(out, "    local line value desc");
+            let _ = writeln!(
+                out,
+                "    for line in \"${{(@f)$({bin} {cc} $((CURRENT - 2)) \"${{(@)words[2,-1]}}\" 2>/dev/null)}}\"; do"
+            );
+            let _ = writeln!(out, "        [[ -n \"$line\" ]] || continue");
+            let _ = writeln!(out, "        value=\"${{line%%$'\\t'*}}\"");
+            let _ = writeln!(out, "        desc=\"${{line#*$'\\t'}}\"");
+            let _ = writeln!(out, "        [[ \"$desc\" == \"$line\" ]] && desc=\"\"");
+            let _ = writeln!(out, "        cands+=(\"${{value//:/\\\\:}}${{desc:+:$desc}}\")");
+            let _ = writeln!(out, "    done");
+            let _ = writeln!(out, "    (( ${{#cands}} )) && _describe 'values' cands || _files");
+            let _ = writeln!(out, "}}");
+            let _ = writeln!(out);
+            let _ = writeln!(out, "compdef _{id} {bin}");
+        }
+        Shell::Fish => {
+            let _ = writeln!(out, "# fish dynamic completion for {bin}");
+            let _ = writeln!(out, "function __{id}_dynamic");
+            let _ = writeln!(out, "    set -l words (commandline -opc)[2..-1] (commandline -ct)");
+            let _ = writeln!(out, "    {bin} {cc} (math (count $words) - 1) $words 2>/dev/null");
+            let _ = writeln!(out, "end");
+            let _ = writeln!(out, "complete -c {bin} -a '(__{id}_dynamic)'");
+        }
+        Shell::PowerShell => {
+            let _ = writeln!(out, "Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{", ps_quote(bin));
+            let _ = writeln!(out, "    param($wordToComplete, $commandAst, $cursorPosition)");
+            let _ = writeln!(out, "    $words = @($commandAst.CommandElements | Select-Object -Skip 1 |");
+            let _ = writeln!(out, "        Where-Object {{ $_.Extent.StartOffset -lt $cursorPosition }} |");
+            let _ = writeln!(out, "        ForEach-Object {{ $_.ToString() }})");
+            let _ = writeln!(out, "    if ($wordToComplete -eq '') {{ $words += '' }}");
+            let _ = writeln!(out, "    & {} {cc} ($words.Count - 1) @words 2>$null | ForEach-Object {{", ps_quote(bin));
+            let _ = writeln!(out, "        $value, $desc = $_ -split \"`t\", 2");
+            let _ = writeln!(out, "        if (-not $desc) {{ $desc = $value }}");
+            let _ = writeln!(
+                out,
+                "        [System.Management.Automation.CompletionResult]::new($value, $value, 'ParameterValue', $desc)"
+            );
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "}}");
+        }
+    }
+    out
+}
@@ -0,0 +1,102 @@
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::Path;
+
+/// A configuration layer consulted after ENV and before defaults (`Source::Config`).
+///
+/// Keys are the scoped keys of [`crate::key_for`]: the option name prefixed by the subcommand path,
+/// e.g. `jobs` or `remote.add.fetch`.
+pub trait ConfigProvider {
+    /// Values configured for `key` (several for repeatable options); empty when not configured.
+    fn get(&self, key: &str) -> Vec<OsString>;
+
+    /// Where `key` is configured, for error messages (e.g. `app.ini:12: key 'jobs'`).
+    fn origin(&self, key: &str) -> String {
+        format!("config key '{key}'")
+    }
+}
+
+/// Dependency-free INI / `key=value` reader.
+///
+/// ```ini
+/// # comments start with '#' or ';'
+/// jobs = 4
+/// [remote.add]
+/// fetch = yes
+/// ```
+///
+/// Section names prefix the keys below them (`remote.add.fetch`). Values are trimmed and may be
+/// wrapped in matching quotes; a key given several times yields several values.
+#[derive(Clone, Debug, Default)]
+pub struct IniConfig {
+    name: String,
+    entries: HashMap<String, Vec<(OsString, usize)>>,
+}
+
+impl IniConfig {
+    /// Parse `text`; `name` (usually the file path) is used in error messages.
+    ///
+    /// # Errors
+    /// `Error::User` naming the file and line of a malformed line.
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self> {
+        let mut cfg = Self { name: name.into(), entries: HashMap::new() };
+        let mut section = String::new();
+        for (n, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(s) = line.strip_prefix('[') {
+                let s = s.strip_suffix(']').ok_or_else(|| cfg.error(n + 1, "unterminated section header"))?;
+                section = s.trim().to_string();
+                continue;
+            }
+            let (k, v) = line.split_once('=').ok_or_else(|| cfg.error(n + 1, "expected 'key = value'"))?;
+            let k = k.trim();
+            if k.is_empty() {
+                return Err(cfg.error(n + 1, "missing key before '='"));
+            }
+            let key = if section.is_empty() { k.to_string() } else { format!("{section}.{k}") };
+            cfg.entries.entry(key).or_default().push((OsString::from(unquote(v.trim())), n + 1));
+        }
+        Ok(cfg)
+    }
+
+    /// Read and parse the file at `path`.
+    ///
+    /// # Errors
+    /// `Error::User` naming the file when it cannot be read or is malformed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::User(format!("cannot read config file '{}': {e}", path.display())))?;
+        Self::parse(path.display().to_string(), &text)
+    }
+
+    fn error(&self, line: usize, msg: &str) -> Error {
+        Error::User(format!("{}:{line}: {msg}", self.name))
+    }
+}
+
+impl ConfigProvider for IniConfig {
+    fn get(&self, key: &str) -> Vec<OsString> {
+        self.entries.get(key).map(|vs| vs.iter().map(|(v, _)| v.clone()).collect()).unwrap_or_default()
+    }
+
+    fn origin(&self, key: &str) -> String {
+        match self.entries.get(key).and_then(|vs| vs.last()) {
+            Some((_, line)) => format!("{}:{line}: key '{key}'", self.name),
+            None => format!("{}: key '{key}'", self.name),
+        }
+    }
+}
+
+fn unquote(v: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(inner) = v.strip_prefix(q).and_then(|s| s.strip_suffix(q)) {
+            return inner;
+        }
+    }
+    v
+}
@@ -0,0 +1,103 @@
+use crate::{CmdSpec, Env, Result};
+use std::ffi::{OsStr, OsString};
+use std::rc::Rc;
+use std::str::FromStr;
+
+pub use rust_args_parser_derive::{Args, Subcommands};
+
+/// Projection from the parse context `P` to the part `S` a derived spec writes into.
+pub type Project<P, S> = Rc<dyn Fn(&mut P) -> &mut S>;
+
+/// A struct whose fields are filled by the parser. Implement with `#[derive(Args)]`.
+pub trait Args: Default + Sized + 'static {
+    /// Root command spec with `Self` as the context.
+    fn command() -> CmdSpec<'static, Self>;
+
+    /// Add the fields of `Self` to `cmd`; callbacks write through `project`.
+    fn augment_args<P: 'static>(cmd: CmdSpec<'static, P>, project: Project<P, Self>) -> CmdSpec<'static, P>;
+
+    /// Parse `argv` (without the program name) into a fresh `Self`.
+    ///
+    /// # Errors
+    /// Same as [`crate::parse`].
+    fn parse_from(env: &Env, argv: &[OsString]) -> Result<Self> {
+        let spec = Self::command();
+        let mut ctx = Self::default();
+        crate::parse(env, &spec, argv, &mut ctx)?;
+        Ok(ctx)
+    }
+}
+
+/// An enum whose variants are subcommands. Implement with `#[derive(Subcommands)]`.
+pub trait Subcommands: Sized + 'static {
+    /// Add one subcommand per variant to `cmd`; the selected one is stored through `project`.
+    fn augment_subcommands<P: 'static>(
+        cmd: CmdSpec<'static, P>,
+        project: Project<P, Option<Self>>,
+    ) -> CmdSpec<'static, P>;
+}
+
+/// Build a [`Project`] from a closure (pins down the higher-ranked signature).
+#[doc(hidden)]
+pub fn project<P, S, F>(f: F) -> Project<P, S>
+where
+    F: Fn(&mut P) -> &mut S + 'static,
+{
+    Rc::new(f)
+}
+
+/// Called by derived `command()`: reject a short option that repeats another one of the same command
+/// (including flattened fields and inherited `global` options) or shadows a built-in (`-h`, `-V`, `-A`).
+/// The derive macro checks the fields of one struct at compile time; this covers the rest.
+///
+/// # Panics
+/// On the first clash, naming both options and the command.
+#[doc(hidden)]
+pub fn check_shorts<P>(cmd: &CmdSpec<'static, P>) {
+    check_shorts_in(cmd, &[]);
+}
+
+fn check_shorts_in<P>(cmd: &CmdSpec<'static, P>, inherited: &[(char, String)]) {
+    let mut seen = inherited.to_vec();
+    let mut globals = inherited.to_vec();
+    for o in cmd.get_opts() {
+        let Some(c) = o.get_short() else { continue };
+        let taken = match c {
+            'h' => Some("--help"),
+            'V' => Some("--version"),
+            'A' => Some("--author"),
+            _ => seen.iter().find(|(s, _)| *s == c).map(|(_, label)| label.as_str()),
+        };
+        if let Some(other) = taken {
+            panic!("short option '-{c}' of '{}' in '{}' is already used by '{other}'", o.label(), cmd.get_name());
+        }
+        seen.push((c, o.label()));
+        if o.is_global() {
+            globals.push((c, o.label()));
+        }
+    }
+    for sub in cmd.get_subcommands() {
+        check_shorts_in(sub, &globals);
+    }
+}
+
+/// Validator used by derived specs: `v` must be UTF-8 and parse as `T`.
+///
+/// # Errors
+/// A message naming `label` (e.g. `--jobs`, `<PATH>`) and the parse error.
+#[doc(hidden)]
+pub fn check_value<T>(label: &str, v: &OsStr) -> core::result::Result<(), String>
+where
+    T: FromStr,
+    T::Err: core::fmt::Display,
+{
+    let s = v.to_str().ok_or_else(|| format!("invalid value for '{label}': not valid UTF-8"))?;
+    s.parse::<T>().map(|_| ()).map_err(|e| format!("invalid value '{s}' for '{label}': {e}"))
+}
+
+/// Value conversion used by derived callbacks (already checked by [`check_value`]).
+#[doc(hidden)]
+#[must_use]
+pub fn parse_value<T: FromStr>(v: &OsStr) -> Option<T> {
+    v.to_str().and_then(|s| s.parse().ok())
+}
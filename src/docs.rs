@@ -0,0 +1,336 @@
+use crate::help::usage_line;
+use crate::spec::PosCardinality;
+use crate::{CmdSpec, Env, OptSpec};
+use core::fmt::Write;
+
+/// Render a single Markdown reference document for `root` and every nested subcommand.
+///
+/// Each command gets an anchor derived from its path (`tool-remote-add`), a usage line, an option table
+/// (short, long, metavar, env, default, group) and a positional table with cardinality.
+#[must_use]
+pub fn render_markdown<Ctx: ?Sized>(env: &Env, root: &CmdSpec<'_, Ctx>) -> String {
+    let docs = collect_docs(env, root);
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", root.get_name());
+    for d in &docs {
+        let indent = "  ".repeat(d.path.len() - 1);
+        let _ = writeln!(out, "{indent}- [{}](#{})", d.path.join(" "), d.anchor);
+    }
+    for d in &docs {
+        let _ = writeln!(out, "\n<a id=\"{}\"></a>\n", d.anchor);
+        let _ = writeln!(out, "## {}\n", d.path.join(" "));
+        if let Some(h) = d.help {
+            let _ = writeln!(out, "{h}\n");
+        }
+        if !d.aliases.is_empty() {
+            let _ = writeln!(
+                out,
+                "**Aliases:** {}\n",
+                d.aliases.iter().map(|a| format!("`{a}`")).collect::<Vec<_>>().join(", ")
+            );
+        }
+        let _ = writeln!(out, "**Usage:** `{}`", d.usage);
+        md_opts(&mut out, "Options", &d.opts);
+        md_opts(&mut out, "Inherited options", &d.inherited);
+        if !d.positionals.is_empty() {
+            let _ = writeln!(out, "\n### Arguments\n");
+            let _ = writeln!(out, "| Name | Cardinality | Description |");
+            let _ = writeln!(out, "|------|-------------|-------------|");
+            for p in &d.positionals {
+                let _ = writeln!(out, "| `{}` | {} | {} |", p.name, p.cardinality, md_cell(p.help.unwrap_or("")));
+            }
+        }
+        if !d.subs.is_empty() {
+            let _ = writeln!(out, "\n### Commands\n");
+            let _ = writeln!(out, "| Command | Aliases | Description |");
+            let _ = writeln!(out, "|---------|---------|-------------|");
+            for s in &d.subs {
+                let aliases = s.aliases.iter().map(|a| format!("`{a}`")).collect::<Vec<_>>().join(", ");
+                let _ =
+                    writeln!(out, "| [`{}`](#{}) | {aliases} | {} |", s.name, s.anchor, md_cell(s.help.unwrap_or("")));
+            }
+        }
+    }
+    out
+}
+
+/// Render the same reference as [`render_markdown`], but as a standalone HTML fragment
+/// (one `<section id="...">` per command).
+#[must_use]
+pub fn render_html<Ctx: ?Sized>(env: &Env, root: &CmdSpec<'_, Ctx>) -> String {
+    let docs = collect_docs(env, root);
+    let mut out = String::new();
+    let _ = writeln!(out, "<h1>{}</h1>", html(root.get_name()));
+    let _ = writeln!(out, "<ul>");
+    for d in &docs {
+        let _ = writeln!(out, "<li><a href=\"#{}\">{}</a></li>", d.anchor, html(&d.path.join(" ")));
+    }
+    let _ = writeln!(out, "</ul>");
+    for d in &docs {
+        let _ = writeln!(out, "<section id=\"{}\">", d.anchor);
+        let _ = writeln!(out, "<h2>{}</h2>", html(&d.path.join(" ")));
+        if let Some(h) = d.help {
+            let _ = writeln!(out, "<p>{}</p>", html(h));
+        }
+        if !d.aliases.is_empty() {
+            let aliases = d.aliases.iter().map(|a| format!("<code>{}</code>", html(a))).collect::<Vec<_>>();
+            let _ = writeln!(out, "<p><strong>Aliases:</strong> {}</p>", aliases.join(", "));
+        }
+        let _ = writeln!(out, "<p><strong>Usage:</strong> <code>{}</code></p>", html(&d.usage));
+        html_opts(&mut out, "Options", &d.opts);
+        html_opts(&mut out, "Inherited options", &d.inherited);
+        if !d.positionals.is_empty() {
+            let _ = writeln!(out, "<h3>Arguments</h3>");
+            let _ = writeln!(out, "<table>");
+            let _ = writeln!(out, "<tr><th>Name</th><th>Cardinality</th><th>Description</th></tr>");
+            for p in &d.positionals {
+                let _ = writeln!(
+                    out,
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                    html(p.name),
+                    html(&p.cardinality),
+                    html(p.help.unwrap_or(""))
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+        if !d.subs.is_empty() {
+            let _ = writeln!(out, "<h3>Commands</h3>");
+            let _ = writeln!(out, "<table>");
+            let _ = writeln!(out, "<tr><th>Command</th><th>Aliases</th><th>Description</th></tr>");
+            for s in &d.subs {
+                let aliases = s.aliases.iter().map(|a| format!("<code>{}</code>", html(a))).collect::<Vec<_>>();
+                let _ = writeln!(
+                    out,
+                    "<tr><td><a href=\"#{}\"><code>{}</code></a></td><td>{}</td><td>{}</td></tr>",
+                    s.anchor,
+                    html(s.name),
+                    aliases.join(", "),
+                    html(s.help.unwrap_or(""))
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+        let _ = writeln!(out, "</section>");
+    }
+    out
+}
+
+// ---------- shared model ----------
+
+struct DocOpt<'s> {
+    short: Option<String>,
+    long: Option<String>,
+    metavar: Option<String>,
+    env: Option<&'s str>,
+    default: Option<String>,
+    group: Option<&'s str>,
+    help: Option<&'s str>,
+}
+
+impl<'s> DocOpt<'s> {
+    fn from_spec<Ctx: ?Sized>(o: &'s OptSpec<'_, Ctx>) -> Self {
+        Self {
+            short: o.get_short().map(|c| format!("-{c}")),
+            long: o.get_long().map(|l| if o.is_negatable() { format!("--[no-]{l}") } else { format!("--{l}") }),
+            metavar: o.get_metavar().or_else(|| o.is_value().then_some("VALUE")).map(|m| {
+                if o.is_optional_value() {
+                    format!("[={m}]")
+                } else {
+                    m.to_string()
+                }
+            }),
+            env: o.get_env(),
+            default: o.get_default().map(|d| d.to_string_lossy().into_owned()),
+            group: o.get_group(),
+            help: o.get_help(),
+        }
+    }
+    fn builtin(short: char, long: &str, help: &'static str) -> Self {
+        Self {
+            short: Some(format!("-{short}")),
+            long: Some(format!("--{long}")),
+            metavar: None,
+            env: None,
+            default: None,
+            group: None,
+            help: Some(help),
+        }
+    }
+}
+
+struct DocPos<'s> {
+    name: &'s str,
+    cardinality: String,
+    help: Option<&'s str>,
+}
+
+struct DocSub<'s> {
+    name: &'s str,
+    anchor: String,
+    aliases: Vec<&'s str>,
+    help: Option<&'s str>,
+}
+
+struct DocCmd<'s> {
+    path: Vec<&'s str>,
+    anchor: String,
+    help: Option<&'s str>,
+    aliases: Vec<&'s str>,
+    usage: String,
+    opts: Vec<DocOpt<'s>>,
+    inherited: Vec<DocOpt<'s>>,
+    positionals: Vec<DocPos<'s>>,
+    subs: Vec<DocSub<'s>>,
+}
+
+fn anchor(path: &[&str]) -> String {
+    path.iter()
+        .map(|p| p.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' }).collect())
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+fn cardinality(card: PosCardinality) -> String {
+    match card {
+        PosCardinality::One { required: true } => "1 (required)".to_string(),
+        PosCardinality::One { required: false } => "0..1".to_string(),
+        PosCardinality::Many => "0..*".to_string(),
+        PosCardinality::Range { min, max: usize::MAX } => format!("{min}..*"),
+        PosCardinality::Range { min, max } => format!("{min}..{max}"),
+    }
+}
+
+fn collect_docs<'s, Ctx: ?Sized>(env: &Env, root: &'s CmdSpec<'_, Ctx>) -> Vec<DocCmd<'s>> {
+    let mut out = Vec::new();
+    let mut path = Vec::new();
+    let mut inherited = Vec::new();
+    collect_rec(env, root, &mut path, &mut inherited, &mut out);
+    out
+}
+
+fn collect_rec<'s, 'a: 's, Ctx: ?Sized>(
+    env: &Env,
+    cmd: &'s CmdSpec<'a, Ctx>,
+    path: &mut Vec<&'s str>,
+    inherited: &mut Vec<&'s OptSpec<'a, Ctx>>,
+    out: &mut Vec<DocCmd<'s>>,
+) {
+    path.push(cmd.get_name());
+    let mut opts = Vec::new();
+    if env.auto_help {
+        opts.push(DocOpt::builtin('h', "help", "Show this help and exit"));
+    }
+    if path.len() == 1 {
+        if env.version.is_some() {
+            opts.push(DocOpt::builtin('V', "version", "Show version and exit"));
+        }
+        if env.author.is_some() {
+            opts.push(DocOpt::builtin('A', "author", "Show author and exit"));
+        }
+    }
+    opts.extend(cmd.get_opts().iter().map(DocOpt::from_spec));
+    let usage = usage_line(path, cmd, inherited);
+    out.push(DocCmd {
+        path: path.clone(),
+        anchor: anchor(path),
+        help: cmd.get_help(),
+        aliases: cmd.get_aliases().to_vec(),
+        usage,
+        opts,
+        inherited: inherited.iter().map(|o| DocOpt::from_spec(*o)).collect(),
+        positionals: cmd
+            .get_positionals()
+            .iter()
+            .map(|p| DocPos { name: p.get_name(), cardinality: cardinality(p.get_cardinality()), help: p.get_help() })
+            .collect(),
+        subs: cmd
+            .get_subcommands()
+            .iter()
+            .map(|sc| {
+                let mut sub_path = path.clone();
+                sub_path.push(sc.get_name());
+                DocSub {
+                    name: sc.get_name(),
+                    anchor: anchor(&sub_path),
+                    aliases: sc.get_aliases().to_vec(),
+                    help: sc.get_help(),
+                }
+            })
+            .collect(),
+    });
+
+    let depth = inherited.len();
+    inherited.extend(cmd.get_opts().iter().filter(|o| o.is_global()));
+    for sc in cmd.get_subcommands() {
+        collect_rec(env, sc, path, inherited, out);
+    }
+    inherited.truncate(depth);
+    path.pop();
+}
+
+// ---------- markup helpers ----------
+
+fn md_cell(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ").replace('|', "\\|")
+}
+
+fn md_code(s: Option<&str>) -> String {
+    s.map_or_else(String::new, |s| format!("`{}`", s.replace('|', "\\|")))
+}
+
+fn md_opts(out: &mut String, title: &str, opts: &[DocOpt<'_>]) {
+    if opts.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "\n### {title}\n");
+    let _ = writeln!(out, "| Short | Long | Value | Env | Default | Group | Description |");
+    let _ = writeln!(out, "|-------|------|-------|-----|---------|-------|-------------|");
+    for o in opts {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            md_code(o.short.as_deref()),
+            md_code(o.long.as_deref()),
+            md_code(o.metavar.as_deref()),
+            md_code(o.env),
+            md_code(o.default.as_deref()),
+            md_code(o.group),
+            md_cell(o.help.unwrap_or(""))
+        );
+    }
+}
+
+fn html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html_code(s: Option<&str>) -> String {
+    s.map_or_else(String::new, |s| format!("<code>{}</code>", html(s)))
+}
+
+fn html_opts(out: &mut String, title: &str, opts: &[DocOpt<'_>]) {
+    if opts.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "<h3>{title}</h3>");
+    let _ = writeln!(out, "<table>");
+    let _ = writeln!(
+        out,
+        "<tr><th>Short</th><th>Long</th><th>Value</th><th>Env</th><th>Default</th><th>Group</th><th>Description</th></tr>"
+    );
+    for o in opts {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            html_code(o.short.as_deref()),
+            html_code(o.long.as_deref()),
+            html_code(o.metavar.as_deref()),
+            html_code(o.env),
+            html_code(o.default.as_deref()),
+            html_code(o.group),
+            html(o.help.unwrap_or(""))
+        );
+    }
+    let _ = writeln!(out, "</table>");
+}
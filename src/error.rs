@@ -12,21 +12,102 @@ pub enum Error {
         code: i32,
         message: Option<String>,
     },
-    /// Rich diagnostics
+    /// Rich diagnostics. `cmd_path` is the command where parsing failed, program name first
+    /// (`["git", "remote", "add"]`); [`Error::render`] shows that command's usage.
     UnknownOption {
         token: String,
         suggestions: Vec<String>,
+        cmd_path: Vec<String>,
     },
     UnknownCommand {
         token: String,
         suggestions: Vec<String>,
+        cmd_path: Vec<String>,
     },
+    /// Too few values for an option (`expected` is the minimum per occurrence, see `OptSpec::num_args`).
     MissingValue {
         opt: String,
+        expected: usize,
+        found: usize,
+        cmd_path: Vec<String>,
     },
+    /// An inline value given to a flag (`--no-color=yes`).
+    UnexpectedValue {
+        opt: String,
+        value: String,
+        cmd_path: Vec<String>,
+    },
+    /// A flag's ENV/config value is not a boolean (or count). `origin` names the source, e.g.
+    /// `environment variable APP_JSON` or `app.ini:3: key 'json'`; `expected` describes valid values.
+    InvalidFlagValue {
+        opt: String,
+        value: String,
+        origin: String,
+        expected: &'static str,
+        cmd_path: Vec<String>,
+    },
+    /// A required positional is absent. `name` is `<FILE>`; `usage` is the command's usage line (empty
+    /// without the `help` feature).
+    MissingPositional {
+        name: String,
+        cmd_path: Vec<String>,
+        usage: String,
+    },
+    /// Fewer values than the positional's `range` minimum.
+    TooFewPositionals {
+        name: String,
+        min: usize,
+        got: usize,
+        cmd_path: Vec<String>,
+        usage: String,
+    },
+    /// A positional beyond the `max` the command accepts.
     UnexpectedPositional {
         token: String,
+        cmd_path: Vec<String>,
+        max: usize,
+        usage: String,
+    },
+    /// Two arguments that cannot be used together (user-facing spellings: `--stdin`, `<FILE>`).
+    ArgumentConflict {
+        arg: String,
+        other: String,
+        cmd_path: Vec<String>,
+    },
+    /// A required argument is absent; `reason` names the rule, e.g. `required by '--key'`.
+    MissingRequired {
+        arg: String,
+        reason: String,
+        cmd_path: Vec<String>,
+    },
+    /// Options marked `required()` that were not given by any source (`--name` spellings).
+    MissingRequiredOptions {
+        opts: Vec<String>,
+        cmd_path: Vec<String>,
+    },
+    /// A `group` rule is broken. Members are CLI spellings (`--stdin`, `<FILE>`): the explicitly given
+    /// ones and those without a value.
+    GroupViolation {
+        group: String,
+        mode: crate::GroupMode,
+        given: Vec<String>,
+        missing: Vec<String>,
+        cmd_path: Vec<String>,
     },
+    /// Several validation errors, collected with `Env::collect_errors`.
+    Multiple(Vec<Self>),
+    /// Value outside the declared `choices` (`opt` is `--name`, `-n` or `<NAME>`).
+    InvalidChoice {
+        opt: String,
+        value: String,
+        allowed: Vec<String>,
+        suggestions: Vec<String>,
+        cmd_path: Vec<String>,
+    },
+}
+
+fn quoted_list(items: &[String]) -> String {
+    items.iter().map(|it| format!("'{it}'")).collect::<Vec<_>>().join(", ")
 }
 
 fn format_alternates(items: &[String]) -> String {
@@ -55,31 +136,263 @@ impl core::fmt::Display for Error {
         match self {
             Self::UserAny(e) => write!(f, "{e}"),
             Self::Parse(s) | Self::User(s) => write!(f, "{s}"),
-            Self::ExitMsg { code, message } => {
-                if let Some(m) = message {
-                    write!(f, "{m}")?;
-                }
-                write!(f, " (exit {code})")
-            }
-            Self::UnknownOption { token, suggestions } => {
+            Self::ExitMsg { message, .. } => f.write_str(message.as_deref().unwrap_or_default()),
+            Self::UnknownOption { token, suggestions, .. } => {
                 write!(f, "unknown option: '{token}'")?;
                 if !suggestions.is_empty() {
                     write!(f, ". Did you mean {}?", format_alternates(suggestions))?;
                 }
                 Ok(())
             }
-            Self::UnknownCommand { token, suggestions } => {
+            Self::UnknownCommand { token, suggestions, .. } => {
                 write!(f, "unknown command: '{token}'")?;
                 if !suggestions.is_empty() {
                     write!(f, ". Did you mean {}?", format_alternates(suggestions))?;
                 }
                 Ok(())
             }
-            Self::MissingValue { opt } => write!(f, "missing value for option: '{opt}'"),
-            Self::UnexpectedPositional { token } => write!(f, "unexpected positional: '{token}'"),
+            Self::MissingValue { opt, expected, found, .. } => {
+                if *expected <= 1 && *found == 0 {
+                    write!(f, "missing value for option: '{opt}'")
+                } else {
+                    write!(f, "option '{opt}' expects {expected} values, found {found}")
+                }
+            }
+            Self::UnexpectedValue { opt, value, .. } => write!(f, "flag '{opt}' takes no value, found '{value}'"),
+            Self::InvalidFlagValue { opt, value, origin, expected, .. } => {
+                write!(f, "{origin}: invalid value '{value}' for '{opt}': expected {expected}")
+            }
+            Self::MissingPositional { name, .. } => write!(f, "missing required positional '{name}'"),
+            Self::TooFewPositionals { name, min, got, .. } => {
+                write!(f, "'{name}' expects at least {min} values, found {got}")
+            }
+            Self::UnexpectedPositional { token, cmd_path, max, .. } => {
+                let cmd = cmd_path.join(" ");
+                match max {
+                    0 => write!(f, "unexpected positional '{token}': '{cmd}' takes no positionals"),
+                    1 => write!(f, "unexpected positional '{token}': '{cmd}' takes at most 1 positional"),
+                    _ => write!(f, "unexpected positional '{token}': '{cmd}' takes at most {max} positionals"),
+                }
+            }
+            Self::ArgumentConflict { arg, other, .. } => write!(f, "'{arg}' cannot be used with '{other}'"),
+            Self::MissingRequired { arg, reason, .. } => write!(f, "missing '{arg}': {reason}"),
+            Self::MissingRequiredOptions { opts, .. } => {
+                let s = if opts.len() == 1 { "" } else { "s" };
+                write!(f, "missing required option{s}: {}", quoted_list(opts))
+            }
+            Self::GroupViolation { group, mode, given, missing, .. } => {
+                use crate::GroupMode;
+                let too_many = given.len() > 1;
+                let all = quoted_list(&[given.as_slice(), missing].concat());
+                let (given, missing) = (quoted_list(given), quoted_list(missing));
+                match mode {
+                    GroupMode::Xor | GroupMode::ExactlyOne if too_many => {
+                        write!(f, "options in group '{group}' are mutually exclusive: {given} were given")
+                    }
+                    GroupMode::Xor | GroupMode::ExactlyOne => {
+                        write!(f, "exactly one of the options in group '{group}' is required: {all}")
+                    }
+                    GroupMode::ReqOne => write!(f, "one of the options in group '{group}' is required: {all}"),
+                    GroupMode::AllOrNone => {
+                        write!(f, "options in group '{group}' must be used together: {given} given, {missing} missing")
+                    }
+                    GroupMode::AtLeast(n) => {
+                        write!(f, "at least {n} of the options in group '{group}' are required: {all}")
+                    }
+                    GroupMode::AtMost(n) => {
+                        write!(f, "at most {n} of the options in group '{group}' may be used: {given} were given")
+                    }
+                }
+            }
+            Self::Multiple(errors) => {
+                write!(f, "{} errors:", errors.len())?;
+                for (i, e) in errors.iter().enumerate() {
+                    write!(f, "\n  {}. {}", i + 1, e.to_string().replace('\n', "\n     "))?;
+                }
+                Ok(())
+            }
+            Self::InvalidChoice { opt, value, allowed, suggestions, .. } => {
+                write!(f, "invalid value for '{opt}': '{value}' (possible: {})", allowed.join(", "))?;
+                if !suggestions.is_empty() {
+                    write!(f, ". Did you mean {}?", format_alternates(suggestions))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+impl Error {
+    /// Process exit code (sysexits-style): the `ExitMsg` code (0 for help/version), 2 (usage) for parse
+    /// errors such as unknown options, missing or invalid values and positional, relation, group or
+    /// required-option errors, 70 (`EX_SOFTWARE`) for `Parse`, and 1 for `User`/`UserAny` (see [`Error::exit_code_with`]).
+    /// `Multiple` uses the code of its first error.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code_with(1)
+    }
+
+    /// Like [`Error::exit_code`], with `user` as the code for `User`/`UserAny` (validators, callbacks).
+    #[must_use]
+    pub fn exit_code_with(&self, user: i32) -> i32 {
+        match self {
+            Self::ExitMsg { code, .. } => *code,
+            Self::User(_) | Self::UserAny(_) => user,
+            Self::Parse(_) => 70,
+            Self::Multiple(errors) => errors.first().map_or(2, |e| e.exit_code_with(user)),
+            Self::UnknownOption { .. }
+            | Self::UnknownCommand { .. }
+            | Self::MissingValue { .. }
+            | Self::UnexpectedValue { .. }
+            | Self::InvalidFlagValue { .. }
+            | Self::MissingPositional { .. }
+            | Self::TooFewPositionals { .. }
+            | Self::UnexpectedPositional { .. }
+            | Self::ArgumentConflict { .. }
+            | Self::MissingRequired { .. }
+            | Self::MissingRequiredOptions { .. }
+            | Self::GroupViolation { .. }
+            | Self::InvalidChoice { .. } => 2,
+        }
+    }
+
+    /// Print and exit with [`Error::exit_code`]: `ExitMsg` messages go to stdout (stderr for a non-zero
+    /// code); other errors are written to stderr with `Error::render` (`help` feature; `error: {e}` otherwise).
+    pub fn report<Ctx: ?Sized>(&self, env: &crate::Env, root: &crate::CmdSpec<'_, Ctx>) -> ! {
+        if let Self::ExitMsg { .. } = self {
+            self.print_exit_msg();
+        } else {
+            #[cfg(feature = "help")]
+            eprint!("{}", self.render(env, root));
+            #[cfg(not(feature = "help"))]
+            {
+                let _ = (env, root);
+                eprintln!("error: {self}");
+            }
+        }
+        std::process::exit(self.exit_code())
+    }
+
+    /// Command path recorded by a parse error (program name first); `None` for errors that carry none.
+    #[cfg(feature = "help")]
+    pub(crate) fn cmd_path(&self) -> Option<&[String]> {
+        match self {
+            Self::UnknownOption { cmd_path, .. }
+            | Self::UnknownCommand { cmd_path, .. }
+            | Self::MissingValue { cmd_path, .. }
+            | Self::UnexpectedValue { cmd_path, .. }
+            | Self::InvalidFlagValue { cmd_path, .. }
+            | Self::MissingPositional { cmd_path, .. }
+            | Self::TooFewPositionals { cmd_path, .. }
+            | Self::UnexpectedPositional { cmd_path, .. }
+            | Self::ArgumentConflict { cmd_path, .. }
+            | Self::MissingRequired { cmd_path, .. }
+            | Self::MissingRequiredOptions { cmd_path, .. }
+            | Self::GroupViolation { cmd_path, .. }
+            | Self::InvalidChoice { cmd_path, .. } => Some(cmd_path),
+            _ => None,
+        }
+    }
+
+    /// Record `names` as the command path of this error (and of collected ones) where none is set yet.
+    pub(crate) fn in_command(mut self, names: &[String]) -> Self {
+        match &mut self {
+            Self::Multiple(errors) => {
+                *errors = core::mem::take(errors).into_iter().map(|e| e.in_command(names)).collect();
+            }
+            Self::UnknownOption { cmd_path, .. }
+            | Self::UnknownCommand { cmd_path, .. }
+            | Self::MissingValue { cmd_path, .. }
+            | Self::UnexpectedValue { cmd_path, .. }
+            | Self::InvalidFlagValue { cmd_path, .. }
+            | Self::MissingPositional { cmd_path, .. }
+            | Self::TooFewPositionals { cmd_path, .. }
+            | Self::UnexpectedPositional { cmd_path, .. }
+            | Self::ArgumentConflict { cmd_path, .. }
+            | Self::MissingRequired { cmd_path, .. }
+            | Self::MissingRequiredOptions { cmd_path, .. }
+            | Self::GroupViolation { cmd_path, .. }
+            | Self::InvalidChoice { cmd_path, .. }
+                if cmd_path.is_empty() =>
+            {
+                cmd_path.extend_from_slice(names);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    fn print_exit_msg(&self) {
+        let _ = self.write_exit_msg(&mut std::io::stdout(), &mut std::io::stderr());
+    }
+
+    /// Write an `ExitMsg` message to `stdout` (code 0) or `stderr`, ending it with exactly one newline: help
+    /// text already has one, `--version` does not.
+    pub(crate) fn write_exit_msg(
+        &self,
+        stdout: &mut impl std::io::Write,
+        stderr: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        let Self::ExitMsg { code, message: Some(m) } = self else { return Ok(()) };
+        let out: &mut dyn std::io::Write = if *code == 0 { stdout } else { stderr };
+        if m.ends_with('\n') {
+            write!(out, "{m}")
+        } else {
+            writeln!(out, "{m}")
         }
     }
 }
+
+/// Return type for `main` (`fn main() -> ap::Exit`), built with `.into()` from a `Result<T>` or an `Error`.
+///
+/// Errors are printed like [`Error::report`] without a spec (`error: {e}` on stderr, help/version text on
+/// stdout) and the process exits with [`Error::exit_code`]; success exits with 0.
+#[derive(Debug)]
+pub struct Exit {
+    error: Option<Error>,
+    user_code: i32,
+}
+
+impl Exit {
+    /// Exit code for `User`/`UserAny` errors (default 1).
+    #[must_use]
+    pub fn user_code(mut self, code: i32) -> Self {
+        self.user_code = code;
+        self
+    }
+    #[must_use]
+    pub fn get_error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+    /// The code the process exits with.
+    #[must_use]
+    pub fn get_code(&self) -> i32 {
+        self.error.as_ref().map_or(0, |e| e.exit_code_with(self.user_code))
+    }
+}
+
+impl<T> From<Result<T>> for Exit {
+    fn from(res: Result<T>) -> Self {
+        Self { error: res.err(), user_code: 1 }
+    }
+}
+
+impl From<Error> for Exit {
+    fn from(e: Error) -> Self {
+        Self { error: Some(e), user_code: 1 }
+    }
+}
+
+impl std::process::Termination for Exit {
+    fn report(self) -> std::process::ExitCode {
+        match &self.error {
+            Some(e @ Error::ExitMsg { .. }) => e.print_exit_msg(),
+            Some(e) => eprintln!("error: {e}"),
+            None => {}
+        }
+        std::process::ExitCode::from(u8::try_from(self.get_code()).unwrap_or(1))
+    }
+}
+
 impl std::error::Error for Error {}
 
 impl From<Box<dyn std::error::Error + Send + Sync + 'static>> for Error {
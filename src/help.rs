@@ -1,137 +1,322 @@
 use crate::util::strip_ansi_len;
-use crate::{CmdSpec, Env};
+use crate::{CmdSpec, Env, Error, OptSpec, Style, Styles};
 use core::fmt::Write;
 
-#[cfg(feature = "color")]
-mod ansi {
-    pub const RESET: &str = "\x1b[0m";
-    pub const BOLD: &str = "\x1b[1m";
-    pub const TITLE: &str = "\x1b[4;37m"; // section titles
-    pub const OPT_LABEL: &str = "\x1b[0;94m"; // option labels
-    pub const POS_LABEL: &str = "\x1b[0;93m"; // positional labels
-    pub const METAVAR: &str = "\x1b[0;96m"; // metavars
-    pub const COMMAND: &str = "\x1b[0;95m"; // command names
-    pub const BRIGHT_WHITE: &str = "\x1b[0;97m";
+/// Styling decided once per render from `Env::color` and `Env::styles`; plain without the `color` feature.
+#[derive(Clone, Copy)]
+struct Paint {
+    styles: Option<Styles>,
 }
 
-#[cfg(feature = "color")]
-fn paint_title(s: &str) -> String {
-    format!("{}{}{}{}:", ansi::BOLD, ansi::TITLE, s, ansi::RESET)
-}
-#[cfg(not(feature = "color"))]
-fn paint_title(s: &str) -> String {
-    s.to_string()
+impl Paint {
+    fn new(env: &Env) -> Self {
+        Self { styles: (cfg!(feature = "color") && env.color.enabled()).then_some(env.styles) }
+    }
+    fn for_stderr(env: &Env) -> Self {
+        Self { styles: (cfg!(feature = "color") && env.color.enabled_on_stderr()).then_some(env.styles) }
+    }
+    fn style(self, pick: fn(&Styles) -> Style, s: &str) -> String {
+        let Some(st) = self.styles else {
+            return s.to_string();
+        };
+        pick(&st).paint(s)
+    }
+    fn title(self, s: &str) -> String {
+        format!("{}:", self.style(|st| st.title, s))
+    }
+    fn section(self, s: &str) -> String {
+        format!("{}:", self.style(|st| st.section, s))
+    }
+    fn option(self, s: &str) -> String {
+        self.style(|st| st.option, s)
+    }
+    fn positional(self, s: &str) -> String {
+        self.style(|st| st.positional, s)
+    }
+    fn metavar(self, s: &str) -> String {
+        self.style(|st| st.metavar, s)
+    }
+    fn command(self, s: &str) -> String {
+        self.style(|st| st.command, s)
+    }
+    fn bin(self, s: &str) -> String {
+        self.style(|st| st.literal, s)
+    }
+    fn error(self, s: &str) -> String {
+        self.style(|st| st.error, s)
+    }
+    /// An offending token: placeholders (`<FILE>`) and literal input styled differently.
+    fn token(self, s: &str) -> String {
+        if s.starts_with('<') {
+            self.style(|st| st.placeholder, s)
+        } else {
+            self.style(|st| st.literal, s)
+        }
+    }
 }
 
-#[cfg(feature = "color")]
-fn paint_section(s: &str) -> String {
-    format!("{}{}{}:", ansi::TITLE, s, ansi::RESET)
+#[must_use]
+pub fn render_help<Ctx: ?Sized>(env: &Env, cmd: &CmdSpec<'_, Ctx>) -> String {
+    render_help_with_path(env, &[], cmd)
 }
-#[cfg(not(feature = "color"))]
-fn paint_section(s: &str) -> String {
-    s.to_string()
+
+/// One element of a usage line (`tool remote add [options] <NAME>`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UsageToken<'s> {
+    /// Program name (first element of the path).
+    Bin(&'s str),
+    /// Subcommand on the path to the described command.
+    Command(&'s str),
+    /// `[options]`
+    Options,
+    /// An option marked `required()`: `--name <VAL>` (`-n`, or `--name[=VAL]` for optional values).
+    RequiredOption { long: Option<&'s str>, short: Option<char>, metavar: Option<&'s str>, optional: bool },
+    /// `<command>` placeholder when the command has subcommands.
+    Subcommand,
+    /// `<NAME>` / `[NAME]`, with `...` when `multiple`.
+    Positional { name: &'s str, required: bool, multiple: bool },
 }
 
-#[cfg(feature = "color")]
-fn paint_option(s: &str) -> String {
-    format!("{}{}{}", ansi::OPT_LABEL, s, ansi::RESET)
+impl core::fmt::Display for UsageToken<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Bin(s) | Self::Command(s) => f.write_str(s),
+            Self::Options => f.write_str("[options]"),
+            Self::Subcommand => f.write_str("<command>"),
+            Self::RequiredOption { long, short, metavar, optional } => {
+                match (long, short) {
+                    (Some(l), _) => write!(f, "--{l}")?,
+                    (None, Some(c)) => write!(f, "-{c}")?,
+                    (None, None) => {}
+                }
+                match (metavar, optional) {
+                    (Some(mv), true) => write!(f, "[={mv}]"),
+                    (Some(mv), false) => write!(f, " <{mv}>"),
+                    (None, _) => Ok(()),
+                }
+            }
+            Self::Positional { name, required, multiple } => {
+                let ellip = if *multiple { "..." } else { "" };
+                if *required {
+                    write!(f, "<{name}{ellip}>")
+                } else {
+                    write!(f, "[{name}{ellip}]")
+                }
+            }
+        }
+    }
 }
-#[cfg(not(feature = "color"))]
-fn paint_option(s: &str) -> String {
-    s.to_string()
+
+/// Usage line of `cmd` reached via `path` (program name first) as structured tokens. `inherited` are the
+/// `global` options of its ancestors, which count towards `[options]`.
+#[must_use]
+pub fn usage_tokens<'s, Ctx: ?Sized>(
+    path: &[&'s str],
+    cmd: &'s CmdSpec<'_, Ctx>,
+    inherited: &[&OptSpec<'_, Ctx>],
+) -> Vec<UsageToken<'s>> {
+    use crate::spec::PosCardinality;
+    let is_root = path.len() <= 1;
+    let mut out = vec![UsageToken::Bin(path.first().copied().unwrap_or(""))];
+    out.extend(path.iter().skip(1).map(|c| UsageToken::Command(c)));
+    let in_usage =
+        |o: &&'s crate::OptSpec<'_, Ctx>| o.is_required() && (o.get_long().is_some() || o.get_short().is_some());
+    if cmd.get_opts().iter().any(|o| !in_usage(&o)) || !inherited.is_empty() || is_root {
+        out.push(UsageToken::Options);
+    }
+    out.extend(cmd.get_opts().iter().filter(in_usage).map(|o| UsageToken::RequiredOption {
+        long: o.get_long(),
+        short: o.get_short(),
+        metavar: o.is_value().then(|| o.get_metavar().unwrap_or("VALUE")),
+        optional: o.is_optional_value(),
+    }));
+    if !cmd.get_subcommands().is_empty() {
+        out.push(UsageToken::Subcommand);
+    }
+    for p in cmd.get_positionals() {
+        let (required, multiple) = match p.get_cardinality() {
+            PosCardinality::One { .. } => (p.is_required(), false),
+            PosCardinality::Many => (p.is_required(), true),
+            PosCardinality::Range { min, max } => (min > 0, max > 1),
+        };
+        out.push(UsageToken::Positional { name: p.get_name(), required, multiple });
+    }
+    out
 }
 
-#[cfg(feature = "color")]
-fn paint_positional(s: &str) -> String {
-    format!("{}{}{}", ansi::POS_LABEL, s, ansi::RESET)
+/// Plain (uncolored) usage line, e.g. `tool remote add [options] <NAME>`.
+#[must_use]
+pub fn usage_line<Ctx: ?Sized>(path: &[&str], cmd: &CmdSpec<'_, Ctx>, inherited: &[&OptSpec<'_, Ctx>]) -> String {
+    usage_tokens(path, cmd, inherited).iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")
 }
-#[cfg(not(feature = "color"))]
-fn paint_positional(s: &str) -> String {
-    s.to_string()
+
+/// Usage line of `cmd` with each token styled.
+fn painted_usage<Ctx: ?Sized>(
+    paint: Paint,
+    path: &[&str],
+    cmd: &CmdSpec<'_, Ctx>,
+    inherited: &[&OptSpec<'_, Ctx>],
+) -> String {
+    let mut parts = Vec::new();
+    for tok in usage_tokens(path, cmd, inherited) {
+        parts.push(match tok {
+            UsageToken::Bin(bin_name) => paint.bin(bin_name),
+            UsageToken::Command(c) => paint.command(c),
+            UsageToken::Options => paint.option(&tok.to_string()),
+            UsageToken::Subcommand => paint.command(&tok.to_string()),
+            UsageToken::RequiredOption { long, short, metavar, optional } => {
+                let spelling = UsageToken::RequiredOption { long, short, metavar: None, optional }.to_string();
+                match (metavar, optional) {
+                    (Some(mv), true) => format!("{}[={}]", paint.option(&spelling), paint.metavar(mv)),
+                    (Some(mv), false) => format!("{} {}", paint.option(&spelling), paint.metavar(&format!("<{mv}>"))),
+                    (None, _) => paint.option(&spelling),
+                }
+            }
+            UsageToken::Positional { .. } => paint.positional(&tok.to_string()),
+        });
+    }
+    parts.join(" ")
 }
 
-#[cfg(feature = "color")]
-fn paint_metavar(s: &str) -> String {
-    format!("{}{}{}", ansi::METAVAR, s, ansi::RESET)
+fn print_usage<Ctx: ?Sized>(
+    out_buf: &mut String,
+    paint: Paint,
+    path: &[&str],
+    cmd: &CmdSpec<'_, Ctx>,
+    inherited: &[&OptSpec<'_, Ctx>],
+) {
+    let _ = writeln!(out_buf, "{}\n  {}\n", paint.title("Usage"), painted_usage(paint, path, cmd, inherited));
 }
-#[cfg(not(feature = "color"))]
-fn paint_metavar(s: &str) -> String {
-    s.to_string()
+
+/// Render help for the leaf of `stack` (root first). `global` options declared by ancestors
+/// are listed under an "Inherited options" section.
+#[must_use]
+pub fn render_help_for_stack<Ctx: ?Sized>(env: &Env, stack: &[&CmdSpec<'_, Ctx>]) -> String {
+    let Some(root) = stack.first() else {
+        return String::new();
+    };
+    render_help_for_stack_as(env, root.get_name(), stack, false)
 }
 
-#[cfg(feature = "color")]
-fn paint_command(s: &str) -> String {
-    format!("{}{}{}", ansi::COMMAND, s, ansi::RESET)
+/// [`render_help_for_stack`] with `bin` as the program name instead of the root's name; `long` selects the
+/// `--help` view (see [`render_long_help_with_path`]).
+#[must_use]
+pub fn render_help_for_stack_as<Ctx: ?Sized>(env: &Env, bin: &str, stack: &[&CmdSpec<'_, Ctx>], long: bool) -> String {
+    let Some((cmd, ancestors)) = stack.split_last() else {
+        return String::new();
+    };
+    let names: Vec<&str> = std::iter::once(bin).chain(stack.iter().skip(1).map(|c| c.get_name())).collect();
+    let inherited: Vec<&OptSpec<'_, Ctx>> =
+        ancestors.iter().flat_map(|c| c.get_opts()).filter(|o| o.is_global()).collect();
+    render_help_impl(env, &names, cmd, &inherited, long)
 }
-#[cfg(not(feature = "color"))]
-fn paint_command(s: &str) -> String {
-    s.to_string()
+
+/// Render help with **strict column alignment** based on the *longest* label in the section.
+#[must_use]
+pub fn render_help_with_path<Ctx: ?Sized>(env: &Env, path: &[&str], cmd: &CmdSpec<'_, Ctx>) -> String {
+    render_help_impl(env, path, cmd, &[], false)
 }
 
+/// Render the `--help` view; [`render_help_with_path`] is the compact `-h` view.
+///
+/// `long_about` and `long_help` texts (falling back to `help`) are wrapped paragraph by paragraph, keeping
+/// blank lines and indented code blocks.
 #[must_use]
-pub fn render_help<Ctx: ?Sized>(env: &Env, cmd: &CmdSpec<'_, Ctx>) -> String {
-    render_help_with_path(env, &[], cmd)
+pub fn render_long_help_with_path<Ctx: ?Sized>(env: &Env, path: &[&str], cmd: &CmdSpec<'_, Ctx>) -> String {
+    render_help_impl(env, path, cmd, &[], true)
 }
 
-fn print_usage<Ctx: ?Sized>(out_buf: &mut String, path: &[&str], cmd: &CmdSpec<'_, Ctx>) {
-    use crate::spec::PosCardinality;
-    let mut out = String::new();
-    let is_root = path.len() <= 1;
-    let _ = writeln!(out, "{}", paint_title("Usage"));
-    let bin_name = (*path.first().unwrap_or(&"")).to_string();
-    #[cfg(feature = "color")]
-    let _ = write!(out, "  {}{}{}{}", ansi::BRIGHT_WHITE, ansi::BOLD, bin_name, ansi::RESET);
-    #[cfg(not(feature = "color"))]
-    let _ = write!(out, "  {}", bin_name);
-    for command in path.iter().skip(1) {
-        let _ = write!(out, " {}", paint_command(command));
-    }
-    if !cmd.get_opts().is_empty() || is_root {
-        #[cfg(feature = "color")]
-        let _ = write!(out, " {}[options]{}", ansi::OPT_LABEL, ansi::RESET);
-        #[cfg(not(feature = "color"))]
-        let _ = write!(out, " [options]");
+/// Labels, `(metavar, optional)` and description of one option row.
+type OptRow<'s> = (Vec<String>, Option<(&'s str, bool)>, String);
+
+fn opt_row<'s, Ctx: ?Sized>(o: &'s OptSpec<'_, Ctx>, long: bool) -> OptRow<'s> {
+    let mut lab = vec![];
+    if let Some(s) = o.get_short() {
+        lab.push(format!("-{s}"));
     }
-    if !cmd.get_subcommands().is_empty() {
-        #[cfg(feature = "color")]
-        let _ = write!(out, " {}<command>{}", ansi::COMMAND, ansi::RESET);
-        #[cfg(not(feature = "color"))]
-        let _ = write!(out, " <command>");
+    if let Some(l) = o.get_long() {
+        lab.push(if o.is_negatable() { format!("--[no-]{l}") } else { format!("--{l}") });
     }
-    for p in cmd.get_positionals() {
-        let name = p.get_name();
-        let (req, ellip) = match p.get_cardinality() {
-            PosCardinality::One { .. } => (p.is_required(), false),
-            PosCardinality::Many => (p.is_required(), true),
-            PosCardinality::Range { min, max } => (min > 0, max > 1),
-        };
-        let token = if ellip { format!("{name}...") } else { name.to_string() };
-        if req {
-            #[cfg(feature = "color")]
-            let _ = write!(out, " {}<{token}>{}", ansi::POS_LABEL, ansi::RESET);
-            #[cfg(not(feature = "color"))]
-            let _ = write!(out, " <{token}>");
-        } else {
-            #[cfg(feature = "color")]
-            let _ = write!(out, " {}[{token}]{}", ansi::POS_LABEL, ansi::RESET);
-            #[cfg(not(feature = "color"))]
-            let _ = write!(out, " [{token}]");
+    let mut desc: Vec<String> = vec![];
+    let long_help = o.get_long_help().filter(|_| long);
+    if let Some(h) = long_help.or_else(|| o.get_help()) {
+        desc.push(h.to_string());
+    }
+    if let Some(env) = o.get_env() {
+        desc.push(format!("Env: {env}"));
+    }
+    if let Some(d) = o.get_default() {
+        desc.push(format!("Default: {d:?}"));
+    }
+    let metavar = if o.is_optional_value() {
+        Some((o.get_metavar().unwrap_or("VALUE"), true))
+    } else {
+        o.get_metavar().map(|m| (m, false))
+    };
+    // after a long text, env/default/choices start a paragraph of their own
+    let desc = match (long_help, desc.split_first()) {
+        (Some(_), Some((h, rest))) if !rest.is_empty() || !o.get_choices().is_empty() => {
+            format!("{h}\n\n{}", with_choices(rest.join("; "), o.get_choices()))
+        }
+        _ => with_choices(desc.join("; "), o.get_choices()),
+    };
+    (lab, metavar, desc)
+}
+
+/// Append `[possible: a, b, c]` to a description when choices are declared.
+fn with_choices(mut desc: String, choices: &[&str]) -> String {
+    if !choices.is_empty() {
+        if !desc.is_empty() {
+            desc.push(' ');
         }
+        let _ = write!(desc, "[possible: {}]", choices.join(", "));
     }
-    let _ = writeln!(out_buf, "{out}\n");
+    desc
 }
 
-/// Render help with **strict column alignment** based on the *longest* label in the section.
-#[allow(clippy::too_many_lines)]
-#[must_use]
-pub fn render_help_with_path<Ctx: ?Sized>(env: &Env, path: &[&str], cmd: &CmdSpec<'_, Ctx>) -> String {
+fn write_opt_rows(out: &mut String, env: &Env, paint: Paint, rows: Vec<OptRow<'_>>, long: bool) {
+    let max_raw = rows
+        .iter()
+        .map(|(opts, pos, _)| opts.join(", ").len() + pos.map_or(0, |(s, opt)| s.len() + if opt { 3 } else { 1 }))
+        .max()
+        .unwrap_or(0);
+    let desc_col = 2 + max_raw + 2; // "  " + label + "  "
+    for (lab, pos, desc) in rows {
+        let mut painted = lab.into_iter().map(|s| paint.option(&s)).collect::<Vec<String>>().join(", ");
+        match pos {
+            Some((pos, true)) => painted.push_str(format!("[={}]", paint.metavar(pos)).as_str()),
+            Some((pos, false)) => painted.push_str(format!(" {}", paint.metavar(pos)).as_str()),
+            None => {}
+        }
+        let raw = strip_ansi_len(&painted);
+        let pad = max_raw + (painted.len() - raw);
+        let _ = write!(out, "  {painted:pad$}  ");
+        wrap_desc(out, &desc, desc_col, env.wrap_cols, long);
+    }
+}
+
+fn render_help_impl<Ctx: ?Sized>(
+    env: &Env,
+    path: &[&str],
+    cmd: &CmdSpec<'_, Ctx>,
+    inherited: &[&OptSpec<'_, Ctx>],
+    long: bool,
+) -> String {
+    let paint = Paint::new(env);
     let mut out = String::new();
-    if let Some(h) = cmd.get_help() {
-        let _ = writeln!(out, "{h}\n");
+    match (cmd.get_long_about().filter(|_| long), cmd.get_help()) {
+        (Some(about), _) => {
+            wrap_paragraphs(&mut out, about, 0, env.wrap_cols);
+            let _ = writeln!(out);
+        }
+        (None, Some(h)) => {
+            let _ = writeln!(out, "{h}\n");
+        }
+        (None, None) => {}
     }
 
-    print_usage(&mut out, path, cmd);
-    let mut rows: Vec<(Vec<String>, Option<&str>, String)> = Vec::new();
+    print_usage(&mut out, paint, path, cmd, inherited);
+    let mut rows: Vec<OptRow<'_>> = Vec::new();
     let is_root = path.len() <= 1;
 
     if env.auto_help {
@@ -148,76 +333,56 @@ pub fn render_help_with_path<Ctx: ?Sized>(env: &Env, path: &[&str], cmd: &CmdSpe
     }
 
     // User‑defined options
-    for o in cmd.get_opts() {
-        let mut lab = vec![];
-        let mut meta: Option<&str> = None;
-        if let Some(s) = o.get_short() {
-            lab.push(format!("-{s}"));
-        }
-        if let Some(l) = o.get_long() {
-            lab.push(format!("--{l}"));
-        }
-        if let Some(mv) = o.get_metavar() {
-            meta = Some(mv);
-        }
-        let mut desc: Vec<String> = vec![];
-        if let Some(h) = o.get_help() {
-            desc.push(h.to_string());
-        }
-        if let Some(env) = o.get_env() {
-            desc.push(format!("Env: {env}"));
-        }
-        if let Some(d) = o.get_default() {
-            desc.push(format!("Default: {d:?}"));
-        }
-        let desc = desc.join("; ");
-        rows.push((lab, meta, desc));
-    }
+    rows.extend(cmd.get_opts().iter().map(|o| opt_row(o, long)));
 
     if !rows.is_empty() {
-        let _ = writeln!(out, "{}", paint_section("Options"));
-        let max_raw =
-            rows.iter().map(|(opts, pos, _)| opts.join(", ").len() + pos.map_or(0, |s| s.len() + 1)).max().unwrap_or(0);
-        let desc_col = 2 + max_raw + 2; // "  " + label + "  "
-        for (lab, pos, desc) in rows {
-            let mut painted = lab.into_iter().map(|s| paint_option(&s)).collect::<Vec<String>>().join(", ");
-            if let Some(pos) = pos {
-                painted.push_str(format!(" {}", paint_metavar(pos)).as_str());
-            }
-            let raw = strip_ansi_len(&painted);
-            let pad = max_raw + (painted.len() - raw);
-            let _ = write!(out, "  {painted:pad$}  ");
-            wrap_after(&mut out, &desc, desc_col, env.wrap_cols);
-        }
+        let _ = writeln!(out, "{}", paint.section("Options"));
+        write_opt_rows(&mut out, env, paint, rows, long);
+    }
+    // Inherited (global) options of ancestor commands
+    if !inherited.is_empty() {
+        let _ = writeln!(out, "\n{}", paint.section("Inherited options"));
+        write_opt_rows(&mut out, env, paint, inherited.iter().map(|o| opt_row(o, long)).collect(), long);
     }
     // Arguments
     if !cmd.get_positionals().is_empty() {
-        let _ = writeln!(out, "\n{}", paint_section("Arguments"));
+        let _ = writeln!(out, "\n{}", paint.section("Arguments"));
         let mut prow_labels: Vec<(String, usize, String)> = Vec::new();
         let mut max_raw = 0usize;
         for p in cmd.get_positionals() {
-            let lab = paint_positional(p.get_name());
+            let lab = paint.positional(p.get_name());
             let raw = strip_ansi_len(&lab);
             max_raw = max_raw.max(raw);
-            prow_labels.push((lab, raw, p.get_help().unwrap_or("").to_string()));
+            let help = p.get_long_help().filter(|_| long).or_else(|| p.get_help()).unwrap_or("");
+            prow_labels.push((lab, raw, with_choices(help.to_string(), p.get_choices())));
         }
         let desc_col = 2 + max_raw + 2;
         for (lab, raw, desc) in prow_labels {
             let pad = max_raw + (lab.len() - raw);
             let _ = write!(out, "  {lab:pad$}  ");
-            wrap_after(&mut out, &desc, desc_col, env.wrap_cols);
+            wrap_desc(&mut out, &desc, desc_col, env.wrap_cols, long);
+        }
+    }
+    // Groups
+    if !cmd.get_groups().is_empty() {
+        let _ = writeln!(out, "\n{}", paint.section("Groups"));
+        let width = cmd.get_groups().iter().map(|g| g.name.len()).max().unwrap_or(0);
+        for g in cmd.get_groups() {
+            let _ = write!(out, "  {:width$}  ", g.name);
+            let desc = format!("{}: {}", g.mode.describe(), group_members(cmd, g.name).join(", "));
+            wrap_after(&mut out, &desc, 2 + width + 2, env.wrap_cols);
         }
     }
     // Commands
     if !cmd.get_subcommands().is_empty() {
-        let _ = writeln!(out, "\n{}", paint_section("Commands"));
+        let _ = writeln!(out, "\n{}", paint.section("Commands"));
         let mut crow_labels: Vec<(String, usize, String)> = Vec::new();
         let mut max_raw = 0usize;
         for sc in cmd.get_subcommands() {
             let name = sc.get_name();
-            let mut lab = vec![paint_command(name)];
+            let mut lab = vec![paint.command(name)];
             for alias in sc.get_aliases() {
-                lab.push(paint_command(alias));
+                lab.push(paint.command(alias));
             }
             let lab = lab.join(", ");
             let raw = strip_ansi_len(&lab);
@@ -234,6 +399,62 @@ pub fn render_help_with_path<Ctx: ?Sized>(env: &Env, path: &[&str], cmd: &CmdSpe
     out
 }
 
+/// CLI spellings of the options and positionals in group `name`.
+fn group_members<Ctx: ?Sized>(cmd: &CmdSpec<'_, Ctx>, name: &str) -> Vec<String> {
+    let opts = cmd.get_opts().iter().filter(|o| o.get_group() == Some(name)).map(OptSpec::label);
+    let pos =
+        cmd.get_positionals().iter().filter(|p| p.get_group() == Some(name)).map(|p| format!("<{}>", p.get_name()));
+    opts.chain(pos).collect()
+}
+
+fn wrap_desc(out: &mut String, text: &str, start_col: usize, wrap: usize, long: bool) {
+    if long {
+        wrap_paragraphs(out, text, start_col, wrap);
+    } else {
+        wrap_after(out, text, start_col, wrap);
+    }
+}
+
+/// Like `wrap_after`, but blank lines separate paragraphs, which are wrapped one by one, and indented
+/// lines (code blocks) are written as is.
+fn wrap_paragraphs(out: &mut String, text: &str, start_col: usize, wrap: usize) {
+    // `None` is a paragraph break; `Some((verbatim, text))` a code line or a paragraph
+    let mut blocks: Vec<Option<(bool, String)>> = Vec::new();
+    for line in text.trim_end().lines() {
+        let last = blocks.last_mut();
+        if line.trim().is_empty() {
+            if matches!(last, Some(Some(_))) {
+                blocks.push(None);
+            }
+        } else if line.starts_with([' ', '\t']) {
+            blocks.push(Some((true, line.trim_end().to_string())));
+        } else if let Some(Some((false, para))) = last {
+            para.push(' ');
+            para.push_str(line.trim());
+        } else {
+            blocks.push(Some((false, line.trim().to_string())));
+        }
+    }
+    if blocks.is_empty() {
+        let _ = writeln!(out);
+        return;
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let Some((verbatim, text)) = block else {
+            let _ = writeln!(out);
+            continue;
+        };
+        if i > 0 {
+            let _ = write!(out, "{}", " ".repeat(start_col));
+        }
+        if *verbatim {
+            let _ = writeln!(out, "{text}");
+        } else {
+            wrap_after(out, text, start_col, wrap);
+        }
+    }
+}
+
 /// Wrap `text` after the already‑printed label. Subsequent lines start at `start_col`.
 fn wrap_after(out: &mut String, text: &str, start_col: usize, wrap: usize) {
     if text.is_empty() {
@@ -265,3 +486,65 @@ fn wrap_after(out: &mut String, text: &str, start_col: usize, wrap: usize) {
     }
     let _ = writeln!(out);
 }
+
+impl Error {
+    /// Render for a terminal: a styled `error:` line per error with the offending token highlighted, the
+    /// usage line of the command where parsing failed (when the error records it; the root otherwise) and a
+    /// `--help` tip. `root` is the spec given to `parse`; `ExitMsg` renders its message unchanged.
+    #[must_use]
+    pub fn render<Ctx: ?Sized>(&self, env: &Env, root: &CmdSpec<'_, Ctx>) -> String {
+        self.render_as(env, root, root.get_name())
+    }
+
+    /// [`Error::render`] with `bin` as the program name instead of the root's name.
+    #[must_use]
+    pub fn render_as<Ctx: ?Sized>(&self, env: &Env, root: &CmdSpec<'_, Ctx>, bin: &str) -> String {
+        if let Self::ExitMsg { message, .. } = self {
+            return message.clone().unwrap_or_default();
+        }
+        let paint = Paint::for_stderr(env);
+        let errors: Vec<&Self> = match self {
+            Self::Multiple(errors) => errors.iter().collect(),
+            other => vec![other],
+        };
+        let mut out = String::new();
+        for e in &errors {
+            let mut msg = e.to_string();
+            for tok in tokens_of(e) {
+                msg = msg.replacen(&format!("'{tok}'"), &format!("'{}'", paint.token(tok)), 1);
+            }
+            let _ = writeln!(out, "{} {msg}", paint.error("error:"));
+        }
+
+        let mut names = vec![bin];
+        let mut inherited = Vec::new();
+        let mut cmd = root;
+        for name in errors.iter().find_map(|e| e.cmd_path()).unwrap_or_default().iter().skip(1) {
+            let Some(sub) = cmd.get_subcommands().iter().find(|c| c.get_name() == name) else { break };
+            names.push(sub.get_name());
+            inherited.extend(cmd.get_opts().iter().filter(|o| o.is_global()));
+            cmd = sub;
+        }
+        let _ = writeln!(out, "\n{} {}", paint.title("Usage"), painted_usage(paint, &names, cmd, &inherited));
+        if env.auto_help {
+            let _ = writeln!(out, "\nFor more information, try '{}'.", paint.token("--help"));
+        }
+        out
+    }
+}
+
+/// User-facing tokens quoted in an error message, highlighted by `render`.
+fn tokens_of(e: &Error) -> Vec<&str> {
+    match e {
+        Error::UnknownOption { token, .. }
+        | Error::UnknownCommand { token, .. }
+        | Error::UnexpectedPositional { token, .. } => vec![token],
+        Error::MissingValue { opt, .. } | Error::UnexpectedValue { opt, .. } => vec![opt],
+        Error::InvalidChoice { value, .. } | Error::InvalidFlagValue { value, .. } => vec![value],
+        Error::ArgumentConflict { arg, .. } | Error::MissingRequired { arg, .. } => vec![arg],
+        Error::MissingPositional { name, .. } | Error::TooFewPositionals { name, .. } => vec![name],
+        Error::MissingRequiredOptions { opts, .. } => opts.iter().map(String::as_str).collect(),
+        Error::GroupViolation { given, missing, .. } => given.iter().chain(missing).map(String::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
@@ -6,6 +6,11 @@ mod matches;
 pub use matches::*;
 mod parse;
 pub use parse::*;
+mod config;
+mod response;
+pub use config::*;
+mod style;
+pub use style::*;
 
 #[cfg(feature = "help")]
 mod help;
@@ -19,5 +24,29 @@ mod suggest;
 #[cfg(feature = "suggest")]
 pub use suggest::*;
 
+#[cfg(feature = "completions")]
+mod completions;
+
+#[cfg(feature = "completions")]
+pub use completions::*;
+
+#[cfg(feature = "man")]
+mod man;
+
+#[cfg(feature = "man")]
+pub use man::*;
+
+#[cfg(feature = "docs")]
+mod docs;
+
+#[cfg(feature = "docs")]
+pub use docs::*;
+
+#[cfg(feature = "derive")]
+mod derive;
+
+#[cfg(feature = "derive")]
+pub use derive::*;
+
 pub mod util;
 pub use util::*;
@@ -0,0 +1,240 @@
+use crate::help::{usage_tokens, UsageToken};
+use crate::{CmdSpec, Env, OptSpec};
+use core::fmt::Write;
+
+/// A rendered man page (`roff`), one per command in the tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManPage {
+    /// Page name, e.g. `tool-remote-add`.
+    pub name: String,
+    /// Manual section (always `1`).
+    pub section: u8,
+    /// Page source in `roff` (`man(7)` macros).
+    pub content: String,
+}
+
+impl ManPage {
+    /// Conventional file name, e.g. `tool-remote-add.1`.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.name, self.section)
+    }
+}
+
+/// Render man pages for `root` and every nested subcommand (`tool(1)`, `tool-remote(1)`,
+/// `tool-remote-add(1)`, ...), root first.
+#[must_use]
+pub fn render_man<Ctx: ?Sized>(env: &Env, root: &CmdSpec<'_, Ctx>) -> Vec<ManPage> {
+    let mut pages = Vec::new();
+    let mut stack = vec![root];
+    man_rec(env, &mut stack, &mut pages);
+    pages
+}
+
+fn man_rec<'s, 'a: 's, Ctx: ?Sized>(env: &Env, stack: &mut Vec<&'s CmdSpec<'a, Ctx>>, pages: &mut Vec<ManPage>) {
+    pages.push(render_page(env, stack));
+    let cmd = stack[stack.len() - 1];
+    for sc in cmd.get_subcommands() {
+        stack.push(sc);
+        man_rec(env, stack, pages);
+        stack.pop();
+    }
+}
+
+/// Escape text for roff: backslashes, hyphens and control characters at line start.
+fn esc(s: &str) -> String {
+    let s = s.replace('\\', "\\e").replace('-', "\\-");
+    s.split('\n')
+        .map(|l| if l.starts_with('.') || l.starts_with('\'') { format!("\\&{l}") } else { l.to_string() })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn page_name(names: &[&str]) -> String {
+    names.join("-")
+}
+
+fn render_page<Ctx: ?Sized>(env: &Env, stack: &[&CmdSpec<'_, Ctx>]) -> ManPage {
+    let names: Vec<&str> = stack.iter().map(|c| c.get_name()).collect();
+    let cmd = stack[stack.len() - 1];
+    let name = page_name(&names);
+    let mut out = String::new();
+
+    let footer = env.version.map_or_else(|| names[0].to_string(), |v| format!("{} {v}", names[0]));
+    let _ =
+        writeln!(out, ".TH \"{}\" \"1\" \"\" \"{}\" \"{}\"", esc(&name.to_uppercase()), esc(&footer), esc(names[0]));
+
+    let _ = writeln!(out, ".SH NAME");
+    match cmd.get_help() {
+        Some(h) => {
+            let _ = writeln!(out, "{} \\- {}", esc(&name), esc(h.lines().next().unwrap_or("")));
+        }
+        None => {
+            let _ = writeln!(out, "{}", esc(&name));
+        }
+    }
+
+    let inherited: Vec<&OptSpec<'_, Ctx>> =
+        stack[..stack.len() - 1].iter().flat_map(|c| c.get_opts()).filter(|o| o.is_global()).collect();
+    let _ = writeln!(out, ".SH SYNOPSIS");
+    let synopsis: Vec<String> = usage_tokens(&names, cmd, &inherited)
+        .into_iter()
+        .map(|t| match t {
+            UsageToken::Bin(s) | UsageToken::Command(s) => format!("\\fB{}\\fR", esc(s)),
+            other => format!("\\fI{}\\fR", esc(&other.to_string())),
+        })
+        .collect();
+    let _ = writeln!(out, "{}", synopsis.join(" "));
+
+    if let Some(h) = cmd.get_long_about().or_else(|| cmd.get_help()) {
+        let _ = writeln!(out, ".SH DESCRIPTION");
+        write_paragraphs(&mut out, h, ".PP");
+    }
+
+    write_options(&mut out, env, cmd, &inherited, stack.len() == 1);
+    write_arguments(&mut out, cmd);
+    write_commands(&mut out, &names, cmd);
+
+    if let Some(author) = env.author {
+        let _ = writeln!(out, ".SH AUTHOR");
+        let _ = writeln!(out, "{}", esc(author));
+    }
+
+    if names.len() > 1 {
+        let _ = writeln!(out, ".SH SEE ALSO");
+        let _ = writeln!(out, "\\fB{}\\fR(1)", esc(&page_name(&names[..names.len() - 1])));
+    }
+
+    ManPage { name, section: 1, content: out }
+}
+
+/// `OPTIONS` (built-ins first; `--version` / `--author` on the root page only) and `INHERITED OPTIONS`.
+fn write_options<Ctx: ?Sized>(
+    out: &mut String,
+    env: &Env,
+    cmd: &CmdSpec<'_, Ctx>,
+    inherited: &[&OptSpec<'_, Ctx>],
+    root: bool,
+) {
+    let _ = writeln!(out, ".SH OPTIONS");
+    if env.auto_help {
+        let _ = writeln!(out, ".TP\n\\fB\\-h\\fR, \\fB\\-\\-help\\fR\nShow this help and exit");
+    }
+    if root {
+        if env.version.is_some() {
+            let _ = writeln!(out, ".TP\n\\fB\\-V\\fR, \\fB\\-\\-version\\fR\nShow version and exit");
+        }
+        if env.author.is_some() {
+            let _ = writeln!(out, ".TP\n\\fB\\-A\\fR, \\fB\\-\\-author\\fR\nShow author and exit");
+        }
+    }
+    for o in cmd.get_opts() {
+        write_opt(out, o);
+    }
+    if !inherited.is_empty() {
+        let _ = writeln!(out, ".SH INHERITED OPTIONS");
+        for o in inherited {
+            write_opt(out, o);
+        }
+    }
+}
+
+fn write_arguments<Ctx: ?Sized>(out: &mut String, cmd: &CmdSpec<'_, Ctx>) {
+    if cmd.get_positionals().is_empty() {
+        return;
+    }
+    let _ = writeln!(out, ".SH ARGUMENTS");
+    for p in cmd.get_positionals() {
+        let _ = writeln!(out, ".TP\n\\fI{}\\fR", esc(p.get_name()));
+        if let Some(h) = p.get_long_help().or_else(|| p.get_help()) {
+            write_paragraphs(out, h, ".IP");
+        }
+    }
+}
+
+/// `COMMANDS`: each subcommand with its aliases, summary and a pointer to its own page.
+fn write_commands<Ctx: ?Sized>(out: &mut String, names: &[&str], cmd: &CmdSpec<'_, Ctx>) {
+    if cmd.get_subcommands().is_empty() {
+        return;
+    }
+    let _ = writeln!(out, ".SH COMMANDS");
+    for sc in cmd.get_subcommands() {
+        let labels: Vec<String> = core::iter::once(sc.get_name())
+            .chain(sc.get_aliases().iter().copied())
+            .map(|n| format!("\\fB{}\\fR", esc(n)))
+            .collect();
+        let _ = writeln!(out, ".TP\n{}", labels.join(", "));
+        if let Some(h) = sc.get_help() {
+            let _ = writeln!(out, "{}", esc(h));
+            let _ = writeln!(out, ".br");
+        }
+        let mut sub = names.to_vec();
+        sub.push(sc.get_name());
+        let _ = writeln!(out, "See \\fB{}\\fR(1).", esc(&page_name(&sub)));
+    }
+}
+
+fn write_opt<Ctx: ?Sized>(out: &mut String, o: &OptSpec<'_, Ctx>) {
+    let mut labels = Vec::new();
+    if let Some(s) = o.get_short() {
+        labels.push(format!("\\fB\\-{}\\fR", esc(&s.to_string())));
+    }
+    if let Some(l) = o.get_long() {
+        let no = if o.is_negatable() { "[no\\-]" } else { "" };
+        labels.push(format!("\\fB\\-\\-{no}{}\\fR", esc(l)));
+    }
+    let mut head = labels.join(", ");
+    if o.is_optional_value() {
+        let _ = write!(head, "[=\\fI{}\\fR]", esc(o.get_metavar().unwrap_or("VALUE")));
+    } else if o.is_value() {
+        let _ = write!(head, " \\fI{}\\fR", esc(o.get_metavar().unwrap_or("VALUE")));
+    }
+    let _ = writeln!(out, ".TP\n{head}");
+    let help = o.get_long_help().or_else(|| o.get_help());
+    if let Some(h) = help {
+        write_paragraphs(out, h, ".IP");
+    }
+    let mut lines = Vec::new();
+    if let Some(env) = o.get_env() {
+        lines.push(format!("Environment: \\fB{}\\fR", esc(env)));
+    }
+    if let Some(d) = o.get_default() {
+        lines.push(format!("Default: \\fI{}\\fR", esc(&d.to_string_lossy())));
+    }
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 || help.is_some() {
+            let _ = writeln!(out, ".br");
+        }
+        let _ = writeln!(out, "{line}");
+    }
+}
+
+/// Emit `text` with blank lines turned into `brk` paragraph breaks (`.PP`, or `.IP` to stay indented
+/// under a `.TP` item) and indented lines (code blocks) kept as is between `.nf` / `.fi`.
+fn write_paragraphs(out: &mut String, text: &str, brk: &str) {
+    let (mut started, mut blank, mut verbatim) = (false, false, false);
+    for line in text.trim_end().lines() {
+        if line.trim().is_empty() {
+            blank = started;
+            continue;
+        }
+        let code = line.starts_with([' ', '\t']);
+        if verbatim && (blank || !code) {
+            let _ = writeln!(out, ".fi");
+            verbatim = false;
+        }
+        if blank {
+            let _ = writeln!(out, "{brk}");
+            blank = false;
+        }
+        if code && !verbatim {
+            let _ = writeln!(out, ".nf");
+            verbatim = true;
+        }
+        let _ = writeln!(out, "{}", esc(line.trim_end()));
+        started = true;
+    }
+    if verbatim {
+        let _ = writeln!(out, ".fi");
+    }
+}
@@ -1,11 +1,15 @@
 use crate::Source;
 use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::sync::Arc;
 
 /// Value container stored in `Matches`.
 #[derive(Clone, Debug)]
 pub enum Value {
     Flag,
+    /// Flag explicitly turned off (`--no-<long>` on a negatable flag).
+    Off,
     One(OsString),
     Many(Vec<OsString>),
 }
@@ -57,6 +61,31 @@ fn pos_key_for_strings(path: &[String], name: &str) -> String {
     }
 }
 
+fn flag_state(v: Option<&Value>) -> Option<bool> {
+    match v {
+        Some(Value::Flag) => Some(true),
+        Some(Value::Off) => Some(false),
+        _ => None,
+    }
+}
+
+fn group_occurrences<'m>(m: &'m Matches, key: &str) -> Option<Vec<&'m [OsString]>> {
+    let all: &[OsString] = match m.values.get(key)? {
+        Value::One(v) => std::slice::from_ref(v),
+        Value::Many(vs) => vs,
+        Value::Flag | Value::Off => return None,
+    };
+    let Some(lens) = m.occurrences.get(key) else { return Some(vec![all]) };
+    let mut out = Vec::with_capacity(lens.len());
+    let mut at = 0;
+    for &n in lens {
+        let end = (at + n).min(all.len());
+        out.push(&all[at..end]);
+        at = end;
+    }
+    Some(out)
+}
+
 /// All parsed values and their sources. Internally keyed by flattened strings.
 /// We add `leaf_path` so callers can query *scoped* without spelling keys.
 #[derive(Debug)]
@@ -64,12 +93,40 @@ pub struct Matches {
     pub(crate) values: HashMap<String, Value>,
     pub(crate) status: HashMap<String, Status>,
     pub(crate) flag_counts: HashMap<String, usize>,
+    /// Number of values contributed by each occurrence of an option, in order.
+    pub(crate) occurrences: HashMap<String, Vec<usize>>,
+    /// Response file of the last CLI occurrence, for keys set from an `@path` file.
+    pub(crate) files: HashMap<String, Arc<Path>>,
     leaf_path: Vec<String>,
 }
 
 impl Matches {
     pub(crate) fn new() -> Self {
-        Self { values: HashMap::new(), status: HashMap::new(), flag_counts: HashMap::new(), leaf_path: Vec::new() }
+        Self {
+            values: HashMap::new(),
+            status: HashMap::new(),
+            flag_counts: HashMap::new(),
+            occurrences: HashMap::new(),
+            files: HashMap::new(),
+            leaf_path: Vec::new(),
+        }
+    }
+
+    /// Mark `key` as set from `src`.
+    pub(crate) fn set_status(&mut self, key: &str, src: Source) {
+        self.status.insert(key.to_string(), Status::Set(src));
+    }
+
+    /// Remember the response file a CLI occurrence of `key` came from (`None` for a plain argument).
+    pub(crate) fn set_file(&mut self, key: &str, file: Option<&Arc<Path>>) {
+        match file {
+            Some(f) => {
+                self.files.insert(key.to_string(), Arc::clone(f));
+            }
+            None => {
+                self.files.remove(key);
+            }
+        }
     }
 
     /// Set the *leaf* (selected) command path. Parser calls this before returning.
@@ -141,6 +198,24 @@ impl Matches {
         }
     }
 
+    /// Values of an option in the leaf scope grouped by occurrence: `--point 1 2 --point 3 4`
+    /// gives `[["1", "2"], ["3", "4"]]`.
+    #[must_use]
+    pub fn get_occurrences(&self, name: &str) -> Option<Vec<&[OsString]>> {
+        group_occurrences(self, &key_for_strings(&self.leaf_path, name))
+    }
+
+    /// Response file (`@path`) that the last CLI occurrence of an option/positional in the leaf scope
+    /// came from; `None` for values typed on the command line or from ENV/defaults.
+    #[must_use]
+    pub fn get_response_file(&self, name: &str) -> Option<&Path> {
+        let files = &self.files;
+        files
+            .get(&key_for_strings(&self.leaf_path, name))
+            .or_else(|| files.get(&pos_key_for_strings(&self.leaf_path, name)))
+            .map(|f| &**f)
+    }
+
     /// Test whether a flag/option was set in the leaf scope (from any Source).
     #[must_use]
     pub fn is_set(&self, name: &str) -> bool {
@@ -162,6 +237,12 @@ impl Matches {
         let k = key_for_strings(&self.leaf_path, name);
         *self.flag_counts.get(&k).unwrap_or(&0)
     }
+
+    /// State of a flag in the leaf scope: `Some(true)` if set, `Some(false)` if negated, `None` if absent.
+    #[must_use]
+    pub fn get_flag(&self, name: &str) -> Option<bool> {
+        flag_state(self.values.get(&key_for_strings(&self.leaf_path, name)))
+    }
 }
 
 /// Read‑only scoped accessor into `Matches`.
@@ -200,6 +281,12 @@ impl MatchView<'_> {
         *self.m.flag_counts.get(&k).unwrap_or(&0)
     }
 
+    /// State of a flag in this scope: `Some(true)` if set, `Some(false)` if negated, `None` if absent.
+    #[must_use]
+    pub fn flag(&self, name: &str) -> Option<bool> {
+        flag_state(self.m.values.get(&key_for(&self.path, name)))
+    }
+
     /// Get a single **option** value (first of many if repeated).
     #[must_use]
     pub fn value(&self, name: &str) -> Option<&OsStr> {
@@ -207,7 +294,7 @@ impl MatchView<'_> {
         match self.m.values.get(&k) {
             Some(Value::One(v)) => Some(v.as_os_str()),
             Some(Value::Many(vs)) => vs.first().map(std::ffi::OsString::as_os_str),
-            Some(Value::Flag) | None => None,
+            Some(Value::Flag | Value::Off) | None => None,
         }
     }
 
@@ -222,6 +309,19 @@ impl MatchView<'_> {
         }
     }
 
+    /// Get **option** values grouped by occurrence (see [`Matches::get_occurrences`]).
+    #[must_use]
+    pub fn occurrences(&self, name: &str) -> Option<Vec<&[OsString]>> {
+        group_occurrences(self.m, &key_for(&self.path, name))
+    }
+
+    /// Response file the last CLI occurrence came from (see [`Matches::get_response_file`]).
+    #[must_use]
+    pub fn response_file(&self, name: &str) -> Option<&Path> {
+        let files = &self.m.files;
+        files.get(&key_for(&self.path, name)).or_else(|| files.get(&pos_key_for(&self.path, name))).map(|f| &**f)
+    }
+
     /// Get the first **positional** with `name`.
     #[must_use]
     pub fn pos_one(&self, name: &str) -> Option<&OsStr> {
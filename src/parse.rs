@@ -2,10 +2,12 @@ use crate::matches::{key_for, pos_key_for};
 #[cfg(feature = "suggest")]
 use crate::suggest::levenshtein;
 use crate::util::looks_like_number_token;
-use crate::{CmdSpec, Env, Error, GroupMode, Repeat, Result, Source};
+use crate::{CmdSpec, ConfigProvider, Env, Error, GroupMode, Repeat, Result, Source};
 use crate::{Matches, Status, Value};
 use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::sync::Arc;
 
 /// Parse command line arguments.
 /// # Errors [`Error`]
@@ -15,19 +17,79 @@ pub fn parse<'a, Ctx: ?Sized>(
     argv: &[OsString],
     ctx: &mut Ctx,
 ) -> Result<Matches> {
+    parse_impl(env, root, root.get_name(), argv, ctx)
+}
+
+/// Parse `std::env::args_os()` and report the outcome for `fn main() -> ExitCode`.
+///
+/// Help/version text goes to stdout, errors (rendered with usage) to stderr, and the returned code follows
+/// [`Error::exit_code`]. The program name shown in messages is taken from `argv[0]`.
+pub fn run<'a, Ctx: ?Sized>(env: &Env, root: &'a CmdSpec<'a, Ctx>, ctx: &mut Ctx) -> std::process::ExitCode {
+    let argv: Vec<OsString> = std::env::args_os().collect();
+    let code = run_from(env, root, &argv, ctx, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::ExitCode::from(u8::try_from(code).unwrap_or(1))
+}
+
+/// [`run`] over `argv` (including the program name in `argv[0]`), writing to `stdout`/`stderr`.
+/// Returns the exit code.
+pub fn run_from<'a, Ctx: ?Sized>(
+    env: &Env,
+    root: &'a CmdSpec<'a, Ctx>,
+    argv: &[OsString],
+    ctx: &mut Ctx,
+    stdout: &mut impl std::io::Write,
+    stderr: &mut impl std::io::Write,
+) -> i32 {
+    let bin = argv.first().map_or_else(|| root.get_name().to_string(), |a| program_name(a));
+    let Err(e) = parse_impl(env, root, &bin, argv.get(1..).unwrap_or_default(), ctx) else {
+        return 0;
+    };
+    // a closed pipe is no reason to change the exit code
+    let _ = match &e {
+        Error::ExitMsg { .. } => e.write_exit_msg(stdout, stderr),
+        #[cfg(feature = "help")]
+        _ => write!(stderr, "{}", e.render_as(env, root, &bin)),
+        #[cfg(not(feature = "help"))]
+        _ => writeln!(stderr, "error: {e}"),
+    };
+    e.exit_code()
+}
+
+// File name of `argv[0]`, without a Windows `.exe`.
+fn program_name(arg0: &OsStr) -> String {
+    let name = std::path::Path::new(arg0).file_name().unwrap_or(arg0).to_string_lossy();
+    name.strip_suffix(".exe").unwrap_or(&name).to_string()
+}
+
+// `bin` is the program name shown in help and error messages.
+fn parse_impl<'a, Ctx: ?Sized>(
+    env: &Env,
+    root: &'a CmdSpec<'a, Ctx>,
+    bin: &str,
+    argv: &[OsString],
+    ctx: &mut Ctx,
+) -> Result<Matches> {
+    #[cfg(feature = "completions")]
+    if env.dynamic_completions && matches!(argv.first(), Some(a) if a == COMPLETE_COMMAND) {
+        return Err(complete_request(env, root, &argv[1..]));
+    }
+    let (expanded, origins): (Vec<OsString>, Vec<_>) =
+        if env.response_files { crate::response::expand(argv)?.into_iter().unzip() } else { (Vec::new(), Vec::new()) };
+    let argv = if env.response_files { &expanded } else { argv };
     let mut m = Matches::new();
     let mut cursor = ParseCursor::new(root);
     cursor.eager_overlay_here(&mut m);
     let mut i = 0usize;
     while i < argv.len() {
         let tok = &argv[i];
+        let file = origins.get(i).and_then(Option::as_ref);
         if !cursor.positional_only {
             if tok == "--" {
                 i += 1;
                 cursor.positional_only = true;
                 continue;
             }
-            if let Some(e) = try_handle_builtins(env, &cursor.stack, cursor.current, tok) {
+            if let Some(e) = try_handle_builtins(env, bin, &cursor.stack, tok) {
                 return Err(e);
             }
             if let Some(sub) = try_select_subcommand(cursor.current, tok) {
@@ -36,14 +98,12 @@ pub fn parse<'a, Ctx: ?Sized>(
                 cursor.eager_overlay_here(&mut m);
                 continue;
             }
-            if let Some(consumed) = try_parse_long(env, cursor.current, &mut m, &cursor.path, &cursor.long_ix, argv, i)?
-            {
+            let here = |e: Error| e.in_command(&command_names(bin, &cursor.path));
+            if let Some(consumed) = try_parse_long(env, &cursor, &mut m, argv, i, file).map_err(here)? {
                 i += consumed;
                 continue;
             }
-            if let Some(consumed) =
-                try_parse_short_or_numeric(env, cursor.current, &mut m, &cursor.path, &cursor.short_ix, argv, i)?
-            {
+            if let Some(consumed) = try_parse_short_or_numeric(env, &cursor, &mut m, argv, i, file).map_err(here)? {
                 i += consumed;
                 continue;
             }
@@ -52,25 +112,34 @@ pub fn parse<'a, Ctx: ?Sized>(
                     && !cursor.current.get_subcommands().is_empty()
                     && cursor.current.get_positionals().get(cursor.pos_idx).is_none()
                 {
-                    return Err(unknown_command_error(env, s, cursor.current));
+                    return Err(here(unknown_command_error(env, s, cursor.current)));
                 }
             }
         }
         // Positional
-        if let Some(consumed) =
-            try_push_positional(cursor.current, &mut m, &cursor.path, &mut cursor.pos_idx, &mut cursor.pos_counts, tok)
-        {
+        if let Some(consumed) = try_push_positional(
+            cursor.current,
+            &mut m,
+            &cursor.path,
+            &mut cursor.pos_idx,
+            &mut cursor.pos_counts,
+            tok,
+            file,
+        ) {
             i += consumed;
             continue;
         }
-        return Err(Error::UnexpectedPositional { token: os_dbg(tok) });
+        return Err(unexpected_positional(bin, &cursor.path, &cursor.stack, tok));
     }
 
-    walk_levels(&cursor.stack, |path, cmd| {
-        overlay_env_and_defaults(&mut m, path, cmd);
-        validate_level(&m, path, cmd)
+    let mut errs = Errors::new(env);
+    walk_levels(&cursor.stack, |path, levels| {
+        errs.enter(bin, path);
+        overlay_sources(&mut errs, &mut m, path, leaf_of(levels), root.get_config())?;
+        validate_level(env, &mut errs, bin, root.get_config(), &m, path, levels)
     })?;
-    walk_levels(&cursor.stack, |path, cmd| run_callbacks(&m, path, cmd, ctx))?;
+    errs.finish()?;
+    walk_levels(&cursor.stack, |path, levels| run_callbacks(&m, path, leaf_of(levels), ctx))?;
     // Execute **leaf** command handler if any
     if let Some(leaf) = cursor.stack.last() {
         if let Some(h) = leaf.get_handler() {
@@ -98,30 +167,29 @@ fn unknown_command_error<Ctx: ?Sized>(env: &Env, name: &str, cmd: &CmdSpec<'_, C
     } else {
         vec![]
     };
-    Error::UnknownCommand { token: name.to_string(), suggestions }
+    Error::UnknownCommand { token: name.to_string(), suggestions, cmd_path: Vec::new() }
 }
 #[cfg(not(feature = "suggest"))]
 fn unknown_command_error<Ctx: ?Sized>(_: &Env, name: &str, _: &CmdSpec<'_, Ctx>) -> Error {
-    Error::UnknownCommand { token: name.to_string(), suggestions: vec![] }
+    Error::UnknownCommand { token: name.to_string(), suggestions: vec![], cmd_path: Vec::new() }
 }
 
 fn try_handle_builtins<Ctx: ?Sized>(
     env: &Env,
+    bin: &str,
     stack: &[&CmdSpec<'_, Ctx>],
-    current: &CmdSpec<'_, Ctx>,
     tok: &OsString,
 ) -> Option<Error> {
     let s = tok.to_str()?;
     if env.auto_help && (s == "-h" || s == "--help") {
         #[cfg(feature = "help")]
         {
-            let names: Vec<&str> = stack.iter().map(|c| c.get_name()).collect();
-            let msg = crate::help::render_help_with_path(env, &names, current);
+            let msg = crate::help::render_help_for_stack_as(env, bin, stack, s == "--help");
             return Some(Error::ExitMsg { code: 0, message: Some(msg) });
         }
         #[cfg(not(feature = "help"))]
         {
-            let _ = current;
+            let _ = (bin, stack);
             return Some(Error::ExitMsg { code: 0, message: None });
         }
     }
@@ -148,15 +216,15 @@ fn try_select_subcommand<'a, Ctx: ?Sized>(
     current.find_sub(s)
 }
 
-fn try_parse_long<'a, Ctx: ?Sized>(
+fn try_parse_long<Ctx: ?Sized>(
     env: &Env,
-    current: &CmdSpec<'a, Ctx>,
+    cursor: &ParseCursor<'_, Ctx>,
     m: &mut Matches,
-    path: &[&str],
-    long_ix: &HashMap<&'a str, usize>,
     argv: &[OsString],
     i: usize,
+    file: Option<&Arc<Path>>,
 ) -> Result<Option<usize>> {
+    let (stack, path, long_ix) = (&cursor.stack, &cursor.path, &cursor.long_ix);
     let Some(s) = argv[i].to_str() else { return Ok(None) };
     if !s.starts_with("--") {
         return Ok(None);
@@ -168,35 +236,56 @@ fn try_parse_long<'a, Ctx: ?Sized>(
     };
     let val_inline = it.next();
 
-    let Some(&idx) = long_ix.get(name) else {
-        return Err(unknown_long_error(env, name, current, path));
+    let Some(&(depth, idx)) = long_ix.get(name) else {
+        if let Some(&(depth, idx)) = name.strip_prefix("no-").and_then(|n| long_ix.get(n)) {
+            let opt = &stack[depth].get_opts()[idx];
+            if opt.is_negatable() {
+                if let Some(v) = val_inline {
+                    return Err(Error::UnexpectedValue {
+                        opt: format!("--{name}"),
+                        value: v.to_string(),
+                        cmd_path: Vec::new(),
+                    });
+                }
+                let key = key_for(&path[..depth], opt.get_name());
+                unset_flag(m, &key, Source::Cli);
+                m.set_file(&key, file);
+                return Ok(Some(1));
+            }
+        }
+        return Err(unknown_long_error(env, name, long_ix, path));
     };
-    let opt = &current.get_opts()[idx];
-    let key = key_for(path, opt.get_name());
+    let opt = &stack[depth].get_opts()[idx];
+    let key = key_for(&path[..depth], opt.get_name());
 
     if opt.is_value() {
-        let v = if let Some(v) = val_inline {
-            OsString::from(v)
-        } else {
-            argv.get(i + 1).cloned().ok_or(Error::MissingValue { opt: format!("--{name}") })?
-        };
-        set_val(m, &key, v, Source::Cli, opt.get_repeat());
-        Ok(Some(if val_inline.is_some() { 1 } else { 2 }))
+        if let (None, Some(missing)) = (val_inline, opt.get_missing_value()) {
+            set_val(m, &key, missing.to_os_string(), Source::Cli, opt.get_repeat());
+            m.set_file(&key, file);
+            return Ok(Some(1));
+        }
+        let (vals, consumed) = take_values(opt, val_inline.map(OsString::from), argv, i, || format!("--{name}"))?;
+        set_vals(m, &key, split_values(vals, opt.get_delimiter()), Source::Cli, opt.get_repeat());
+        m.set_file(&key, file);
+        Ok(Some(consumed))
+    } else if let Some(v) = val_inline {
+        Err(Error::UnexpectedValue { opt: format!("--{name}"), value: v.to_string(), cmd_path: Vec::new() })
     } else {
         set_flag(m, &key, Source::Cli);
+        m.set_file(&key, file);
         Ok(Some(1))
     }
 }
 
 fn try_parse_short_or_numeric<Ctx: ?Sized>(
     env: &Env,
-    current: &CmdSpec<'_, Ctx>,
+    cursor: &ParseCursor<'_, Ctx>,
     m: &mut Matches,
-    path: &[&str],
-    short_ix: &HashMap<char, usize>,
     argv: &[OsString],
     i: usize,
+    file: Option<&Arc<Path>>,
 ) -> Result<Option<usize>> {
+    let (stack, path, short_ix) = (&cursor.stack, &cursor.path, &cursor.short_ix);
     let Some(s) = argv[i].to_str() else { return Ok(None) };
     let Some(rest) = s.strip_prefix('-') else { return Ok(None) };
     if rest.is_empty() {
@@ -213,26 +302,80 @@ fn try_parse_short_or_numeric<Ctx: ?Sized>(
     // Cluster walk
     let mut chars = rest.chars().peekable();
     while let Some(c) = chars.next() {
-        let Some(&idx) = short_ix.get(&c) else {
-            return Err(unknown_short_error(env, c, current, path));
+        let Some(&(depth, idx)) = short_ix.get(&c) else {
+            return Err(unknown_short_error(env, c, short_ix, path));
         };
-        let opt = &current.get_opts()[idx];
-        let key = key_for(path, opt.get_name());
+        let opt = &stack[depth].get_opts()[idx];
+        let key = key_for(&path[..depth], opt.get_name());
         if opt.is_value() {
-            if chars.peek().is_some() {
-                let r: String = chars.collect();
-                set_val(m, &key, OsString::from(r), Source::Cli, opt.get_repeat());
+            let mut attached: Option<String> = chars.peek().is_some().then(|| chars.collect());
+            // `-c=never` spells the value like `--color=never` when it is optional
+            if let (Some(v), true) = (&mut attached, opt.is_optional_value()) {
+                if v.starts_with('=') {
+                    v.remove(0);
+                }
+            }
+            let attached = attached.map(OsString::from);
+            if let (None, Some(missing)) = (&attached, opt.get_missing_value()) {
+                set_val(m, &key, missing.to_os_string(), Source::Cli, opt.get_repeat());
+                m.set_file(&key, file);
                 return Ok(Some(1));
             }
-            let v = argv.get(i + 1).cloned().ok_or(Error::MissingValue { opt: format!("-{c}") })?;
-            set_val(m, &key, v, Source::Cli, opt.get_repeat());
-            return Ok(Some(2));
+            let (vals, consumed) = take_values(opt, attached, argv, i, || format!("-{c}"))?;
+            set_vals(m, &key, split_values(vals, opt.get_delimiter()), Source::Cli, opt.get_repeat());
+            m.set_file(&key, file);
+            return Ok(Some(consumed));
         }
         set_flag(m, &key, Source::Cli);
+        m.set_file(&key, file);
     }
     Ok(Some(1))
 }
 
+/// Collect the values of one occurrence of `opt` found at `argv[i]`; `attached` is the `=value` /
+/// `-jVALUE` part. Returns the values and the number of tokens consumed (including the option).
+///
+/// Without `num_args` exactly one value is taken, even if the next token looks like an option.
+/// With `num_args(min, max)` up to `max` values are taken until an option-looking token or `--`.
+fn take_values<Ctx: ?Sized>(
+    opt: &crate::OptSpec<'_, Ctx>,
+    attached: Option<OsString>,
+    argv: &[OsString],
+    i: usize,
+    label: impl Fn() -> String,
+) -> Result<(Vec<OsString>, usize)> {
+    let Some((min, max)) = opt.get_num_args() else {
+        if let Some(v) = attached {
+            return Ok((vec![v], 1));
+        }
+        let v = argv.get(i + 1).ok_or_else(|| Error::MissingValue {
+            opt: label(),
+            expected: 1,
+            found: 0,
+            cmd_path: Vec::new(),
+        })?;
+        return Ok((vec![v.clone()], 2));
+    };
+    let mut vals: Vec<OsString> = attached.into_iter().collect();
+    let mut j = i + 1;
+    while vals.len() < max {
+        match argv.get(j) {
+            Some(t) if !looks_like_option(t) => vals.push(t.clone()),
+            _ => break,
+        }
+        j += 1;
+    }
+    if vals.len() < min {
+        return Err(Error::MissingValue { opt: label(), expected: min, found: vals.len(), cmd_path: Vec::new() });
+    }
+    Ok((vals, j - i))
+}
+
+/// `--`, `--long`, `-s`; not `-` alone or negative numbers.
+fn looks_like_option(t: &OsStr) -> bool {
+    t.to_str().is_some_and(|s| s.len() > 1 && s.starts_with('-') && !looks_like_number_token(s))
+}
+
 fn try_push_positional<Ctx: ?Sized>(
     current: &CmdSpec<'_, Ctx>,
     m: &mut Matches,
@@ -240,13 +383,20 @@ fn try_push_positional<Ctx: ?Sized>(
     pos_idx: &mut usize,
     pos_counts: &mut [usize],
     tok: &OsString,
+    file: Option<&Arc<Path>>,
 ) -> Option<usize> {
     let pos = current.get_positionals().get(*pos_idx)?;
     let key = pos_key_for(path, pos.get_name());
     push_pos(m, &key, tok.clone());
+    m.set_file(&key, file);
+    advance_positional(pos.get_cardinality(), pos_idx, pos_counts);
+    Some(1)
+}
+
+fn advance_positional(card: crate::spec::PosCardinality, pos_idx: &mut usize, pos_counts: &mut [usize]) {
     pos_counts[*pos_idx] += 1;
     // advance if capacity reached
-    match pos.get_cardinality() {
+    match card {
         crate::spec::PosCardinality::One { .. } => {
             *pos_idx += 1;
         }
@@ -257,82 +407,224 @@ fn try_push_positional<Ctx: ?Sized>(
             }
         }
     }
-    Some(1)
 }
 
+/// Location of an option visible at the current level: `(depth in the command stack, index into its opts)`.
+type OptLoc = (usize, usize);
+
+/// Index every option visible at the top of `stack`: the leaf's own options plus `global` options
+/// of its ancestors. Deeper declarations shadow shallower ones with the same spelling.
 fn rebuild_indexes<'a, Ctx: ?Sized>(
-    cmd: &'a CmdSpec<'_, Ctx>,
-    long: &mut HashMap<&'a str, usize>,
-    short: &mut HashMap<char, usize>,
+    stack: &[&'a CmdSpec<'a, Ctx>],
+    long: &mut HashMap<&'a str, OptLoc>,
+    short: &mut HashMap<char, OptLoc>,
 ) {
     long.clear();
     short.clear();
-    for (i, o) in cmd.get_opts().iter().enumerate() {
-        if let Some(l) = o.get_long() {
-            long.insert(l, i);
-        }
-        if let Some(s) = o.get_short() {
-            short.insert(s, i);
+    let leaf = stack.len() - 1;
+    for (depth, cmd) in stack.iter().enumerate() {
+        for (i, o) in cmd.get_opts().iter().enumerate() {
+            if depth < leaf && !o.is_global() {
+                continue;
+            }
+            if let Some(l) = o.get_long() {
+                long.insert(l, (depth, i));
+            }
+            if let Some(s) = o.get_short() {
+                short.insert(s, (depth, i));
+            }
         }
     }
 }
 
-fn eager_overlay<Ctx: ?Sized>(m: &mut Matches, path: &[&str], cmd: &CmdSpec<'_, Ctx>, src: Source) {
+/// Fill unset options of `cmd` from `src`. Every option is processed; the first error (a flag
+/// value that is not a boolean/count) is returned and that option stays unset.
+fn eager_overlay<Ctx: ?Sized>(
+    m: &mut Matches,
+    path: &[&str],
+    cmd: &CmdSpec<'_, Ctx>,
+    config: Option<&dyn ConfigProvider>,
+    src: Source,
+) -> Result<()> {
+    let mut first_err = None;
     for o in cmd.get_opts() {
         let k = key_for(path, o.get_name());
-        if !m.status.contains_key(&k) {
-            match src {
-                Source::Env => {
-                    if let Some(var) = o.get_env() {
-                        if let Some(v) = std::env::var_os(var) {
-                            if o.is_value() {
-                                set_val(m, &k, v, Source::Env, o.get_repeat());
-                            } else {
-                                set_flag(m, &k, Source::Env);
-                            }
-                        }
-                    }
-                }
-                Source::Default => {
-                    if let Some(d) = o.get_default() {
-                        set_val(m, &k, d.clone(), Source::Default, o.get_repeat());
-                    }
+        if m.status.contains_key(&k) {
+            continue;
+        }
+        let res = match src {
+            Source::Env => overlay_env(m, &k, o),
+            Source::Config => config.map_or(Ok(()), |c| overlay_config(m, &k, o, c)),
+            Source::Default => {
+                if let Some(d) = o.get_default() {
+                    let vals = split_values(vec![d.clone()], o.get_delimiter());
+                    set_split(m, &k, vals, Source::Default, o.get_repeat());
                 }
-                Source::Cli => {}
+                Ok(())
             }
+            Source::Cli => Ok(()),
+        };
+        if let Err(e) = res {
+            first_err.get_or_insert(e);
         }
     }
+    first_err.map_or(Ok(()), Err)
 }
 
+fn overlay_env<Ctx: ?Sized>(m: &mut Matches, key: &str, o: &crate::OptSpec<'_, Ctx>) -> Result<()> {
+    let Some(var) = o.get_env() else { return Ok(()) };
+    let Some(v) = std::env::var_os(var) else { return Ok(()) };
+    if o.is_value() {
+        set_split(m, key, split_values(vec![v], o.get_env_delimiter()), Source::Env, o.get_repeat());
+        return Ok(());
+    }
+    let text = v.to_string_lossy();
+    let count =
+        flag_count(o, &text).ok_or_else(|| invalid_flag_value(o, &text, format!("environment variable {var}")))?;
+    set_flag_state(m, key, o, count, Source::Env);
+    Ok(())
+}
+
+fn overlay_config<Ctx: ?Sized>(
+    m: &mut Matches,
+    key: &str,
+    o: &crate::OptSpec<'_, Ctx>,
+    config: &dyn ConfigProvider,
+) -> Result<()> {
+    let mut vals = config.get(key);
+    if vals.is_empty() {
+        return Ok(());
+    }
+    if o.is_value() {
+        // a repeated key for a single-valued option behaves like a repeated CLI option: the last one wins
+        if o.get_repeat() == Repeat::Single {
+            vals.drain(..vals.len() - 1);
+        }
+        set_split(m, key, split_values(vals, o.get_delimiter()), Source::Config, o.get_repeat());
+        return Ok(());
+    }
+    let text = vals[vals.len() - 1].to_string_lossy();
+    let count = flag_count(o, &text).ok_or_else(|| invalid_flag_value(o, &text, config.origin(key)))?;
+    set_flag_state(m, key, o, count, Source::Config);
+    Ok(())
+}
+
+/// Occurrence count for a flag given as text (ENV/config): a boolean spelling (see
+/// [`crate::util::parse_bool`]), or an integer when the flag opted in with `count_from_env`.
+fn flag_count<Ctx: ?Sized>(o: &crate::OptSpec<'_, Ctx>, s: &str) -> Option<usize> {
+    if o.is_count_from_env() {
+        if let Ok(n) = s.trim().parse::<usize>() {
+            return Some(n);
+        }
+    }
+    crate::util::parse_bool(s).map(usize::from)
+}
+
+fn invalid_flag_value<Ctx: ?Sized>(o: &crate::OptSpec<'_, Ctx>, text: &str, origin: String) -> Error {
+    let expected = if o.is_count_from_env() {
+        "a count or a boolean (1/0, true/false, yes/no, on/off)"
+    } else {
+        "1/0, true/false, yes/no or on/off"
+    };
+    Error::InvalidFlagValue { opt: o.label(), value: text.to_string(), origin, expected, cmd_path: Vec::new() }
+}
+
+/// Store a flag count from ENV/config. `0` records a negatable flag as off; other flags have no "off"
+/// state, so a falsy value is the same as an unset variable and leaves no entry.
+fn set_flag_state<Ctx: ?Sized>(m: &mut Matches, key: &str, o: &crate::OptSpec<'_, Ctx>, n: usize, src: Source) {
+    if n == 0 {
+        if o.is_negatable() {
+            unset_flag(m, key, src);
+        }
+    } else {
+        set_flag_count(m, key, n, src);
+    }
+}
+
+/// Record one more occurrence. A CLI occurrence replaces a count that came from ENV/default.
 fn set_flag(m: &mut Matches, key: &str, src: Source) {
-    *m.flag_counts.entry(key.to_string()).or_insert(0) += 1;
+    let count = m.flag_counts.entry(key.to_string()).or_insert(0);
+    if src == Source::Cli && !matches!(m.status.get(key), Some(Status::Set(Source::Cli))) {
+        *count = 0;
+    }
+    *count += 1;
     m.values.insert(key.to_string(), Value::Flag);
-    m.status.insert(key.to_string(), Status::Set(src));
+    m.set_status(key, src);
+}
+
+fn set_flag_count(m: &mut Matches, key: &str, n: usize, src: Source) {
+    m.flag_counts.insert(key.to_string(), n);
+    m.values.insert(key.to_string(), Value::Flag);
+    m.set_status(key, src);
+}
+
+/// Record an explicit "off" (`--no-<long>`, or a falsy ENV value of a negatable flag), replacing earlier
+/// occurrences.
+fn unset_flag(m: &mut Matches, key: &str, src: Source) {
+    m.flag_counts.insert(key.to_string(), 0);
+    m.values.insert(key.to_string(), Value::Off);
+    m.set_status(key, src);
 }
 
 fn set_val(m: &mut Matches, key: &str, val: OsString, src: Source, rep: Repeat) {
+    set_vals(m, key, vec![val], src, rep);
+}
+
+/// Record one occurrence carrying `vals`. `Repeat::Single` keeps only the last occurrence, and a
+/// CLI occurrence replaces values that came from ENV/default.
+fn set_vals(m: &mut Matches, key: &str, mut vals: Vec<OsString>, src: Source, rep: Repeat) {
+    if src == Source::Cli && !matches!(m.status.get(key), Some(Status::Set(Source::Cli))) {
+        m.values.remove(key);
+        m.occurrences.remove(key);
+    }
+    let n = vals.len();
     match rep {
         Repeat::Single => {
-            m.values.insert(key.to_string(), Value::One(val));
+            let v = if n == 1 { Value::One(vals.remove(0)) } else { Value::Many(vals) };
+            m.values.insert(key.to_string(), v);
+            m.occurrences.insert(key.to_string(), vec![n]);
         }
         Repeat::Many => {
-            m.values
-                .entry(key.to_string())
-                .and_modify(|v| {
-                    if let Value::Many(vs) = v {
-                        vs.push(val.clone());
-                    }
-                })
-                .or_insert_with(|| Value::Many(vec![val]));
+            match m.values.get_mut(key) {
+                Some(Value::Many(vs)) => vs.append(&mut vals),
+                _ => {
+                    m.values.insert(key.to_string(), Value::Many(vals));
+                }
+            }
+            m.occurrences.entry(key.to_string()).or_default().push(n);
+        }
+    }
+    m.set_status(key, src);
+}
+/// Store pieces of a split ENV/default value: one occurrence each for repeatable options (as if
+/// given several times on the CLI), otherwise a single occurrence holding all pieces.
+fn set_split(m: &mut Matches, key: &str, pieces: Vec<OsString>, src: Source, rep: Repeat) {
+    match rep {
+        Repeat::Many => {
+            for p in pieces {
+                set_vals(m, key, vec![p], src, rep);
+            }
         }
+        Repeat::Single => set_vals(m, key, pieces, src, rep),
     }
-    m.status.insert(key.to_string(), Status::Set(src));
 }
+
+/// Split every value on `sep` (values that are not UTF-8 are kept whole).
+fn split_values(vals: Vec<OsString>, sep: Option<char>) -> Vec<OsString> {
+    let Some(sep) = sep else { return vals };
+    vals.into_iter()
+        .flat_map(|v| {
+            let pieces: Option<Vec<OsString>> = v.to_str().map(|s| s.split(sep).map(OsString::from).collect());
+            pieces.unwrap_or_else(|| vec![v])
+        })
+        .collect()
+}
+
 fn push_pos(m: &mut Matches, key: &str, val: OsString) {
-    use crate::Value::{Flag, Many, One};
+    use crate::Value::{Flag, Many, Off, One};
     match m.values.get_mut(key) {
         Some(Many(vs)) => vs.push(val),
-        Some(One(_) | Flag) => {
+        Some(One(_) | Flag | Off) => {
             if let Some(One(s)) = m.values.remove(key) {
                 m.values.insert(key.to_string(), Many(vec![s, val]));
             }
@@ -341,7 +633,7 @@ fn push_pos(m: &mut Matches, key: &str, val: OsString) {
             m.values.insert(key.to_string(), One(val));
         }
     }
-    m.status.insert(key.to_string(), Status::Set(Source::Cli));
+    m.set_status(key, Source::Cli);
 }
 
 fn os_dbg(s: &OsStr) -> String {
@@ -349,10 +641,9 @@ fn os_dbg(s: &OsStr) -> String {
 }
 
 #[cfg(feature = "suggest")]
-fn unknown_long_error<Ctx: ?Sized>(env: &Env, name: &str, cmd: &CmdSpec<'_, Ctx>, path: &[&str]) -> Error {
+fn unknown_long_error(env: &Env, name: &str, long_ix: &HashMap<&str, OptLoc>, path: &[&str]) -> Error {
     let suggestions = if env.suggest {
-        let mut cands: Vec<String> =
-            cmd.get_opts().iter().filter_map(|o| o.get_long().map(std::string::ToString::to_string)).collect();
+        let mut cands: Vec<String> = long_ix.keys().map(std::string::ToString::to_string).collect();
         if path.is_empty() {
             if env.author.is_some() {
                 cands.push("author".to_string());
@@ -367,18 +658,17 @@ fn unknown_long_error<Ctx: ?Sized>(env: &Env, name: &str, cmd: &CmdSpec<'_, Ctx>
     } else {
         vec![]
     };
-    Error::UnknownOption { token: format!("--{name}"), suggestions }
+    Error::UnknownOption { token: format!("--{name}"), suggestions, cmd_path: Vec::new() }
 }
 #[cfg(not(feature = "suggest"))]
-fn unknown_long_error<Ctx: ?Sized>(_: &Env, name: &str, _: &CmdSpec<'_, Ctx>, _: &[&str]) -> Error {
-    Error::UnknownOption { token: format!("--{}", name), suggestions: vec![] }
+fn unknown_long_error(_: &Env, name: &str, _: &HashMap<&str, OptLoc>, _: &[&str]) -> Error {
+    Error::UnknownOption { token: format!("--{name}"), suggestions: vec![], cmd_path: Vec::new() }
 }
 
 #[cfg(feature = "suggest")]
-fn unknown_short_error<Ctx: ?Sized>(env: &Env, c: char, cmd: &CmdSpec<'_, Ctx>, path: &[&str]) -> Error {
+fn unknown_short_error(env: &Env, c: char, short_ix: &HashMap<char, OptLoc>, path: &[&str]) -> Error {
     let suggestions = if env.suggest {
-        let mut cands: Vec<String> =
-            cmd.get_opts().iter().filter_map(|o| o.get_short().map(|s| s.to_string())).collect();
+        let mut cands: Vec<String> = short_ix.keys().map(char::to_string).collect();
         if path.is_empty() {
             if env.author.is_some() {
                 cands.push("A".into());
@@ -393,11 +683,11 @@ fn unknown_short_error<Ctx: ?Sized>(env: &Env, c: char, cmd: &CmdSpec<'_, Ctx>,
     } else {
         vec![]
     };
-    Error::UnknownOption { token: format!("-{c}"), suggestions }
+    Error::UnknownOption { token: format!("-{c}"), suggestions, cmd_path: Vec::new() }
 }
 #[cfg(not(feature = "suggest"))]
-fn unknown_short_error<Ctx: ?Sized>(_: &Env, c: char, _: &CmdSpec<'_, Ctx>, _: &[&str]) -> Error {
-    Error::UnknownOption { token: format!("-{}", c), suggestions: vec![] }
+fn unknown_short_error(_: &Env, c: char, _: &HashMap<char, OptLoc>, _: &[&str]) -> Error {
+    Error::UnknownOption { token: format!("-{c}"), suggestions: vec![], cmd_path: Vec::new() }
 }
 
 #[cfg(feature = "suggest")]
@@ -407,36 +697,148 @@ fn best_suggestions(needle: &str, hay: &[String]) -> Vec<String> {
     scored.into_iter().filter(|(d, _)| *d <= 2).take(3).map(|(_, s)| s).collect()
 }
 
-/// Walk stack from root→leaf, yielding the *scoped* path (without root) and the cmd.
+/// Walk stack from root→leaf, yielding the *scoped* path (without root) and the levels from the root down
+/// to the visited command (last).
 fn walk_levels<'a, Ctx, F>(stack: &[&'a CmdSpec<'a, Ctx>], mut f: F) -> Result<()>
 where
     Ctx: ?Sized,
-    F: FnMut(&[&'a str], &'a CmdSpec<'a, Ctx>) -> Result<()>,
+    F: FnMut(&[&'a str], &[&'a CmdSpec<'a, Ctx>]) -> Result<()>,
 {
     let mut path: Vec<&'a str> = Vec::with_capacity(stack.len().saturating_sub(1));
     for (idx, cmd) in stack.iter().enumerate() {
         if idx > 0 {
             path.push(cmd.get_name());
         }
-        f(&path, cmd)?;
+        f(&path, &stack[..=idx])?;
     }
     Ok(())
 }
 
-fn overlay_env_and_defaults<Ctx: ?Sized>(m: &mut Matches, path: &[&str], cmd: &CmdSpec<'_, Ctx>) {
-    eager_overlay(m, path, cmd, crate::Source::Env);
-    eager_overlay(m, path, cmd, crate::Source::Default);
+fn leaf_of<'a, Ctx: ?Sized>(levels: &[&'a CmdSpec<'a, Ctx>]) -> &'a CmdSpec<'a, Ctx> {
+    levels[levels.len() - 1]
+}
+
+/// `global` options declared by the ancestors in `levels` (the leaf's own options excluded).
+#[cfg(feature = "help")]
+fn inherited_globals<'a, Ctx: ?Sized>(levels: &[&'a CmdSpec<'a, Ctx>]) -> Vec<&'a crate::OptSpec<'a, Ctx>> {
+    levels[..levels.len() - 1].iter().flat_map(|c| c.get_opts()).filter(|o| o.is_global()).collect()
+}
+
+/// Full command path of `path`, program name first, as recorded on errors.
+fn command_names(bin: &str, path: &[&str]) -> Vec<String> {
+    core::iter::once(bin).chain(path.iter().copied()).map(str::to_string).collect()
+}
+
+/// Full command path (program name first) and the plain usage line of the leaf of `levels`, for
+/// positional errors.
+fn usage_context<Ctx: ?Sized>(bin: &str, path: &[&str], levels: &[&CmdSpec<'_, Ctx>]) -> (Vec<String>, String) {
+    #[cfg(feature = "help")]
+    let usage = {
+        let names: Vec<&str> = core::iter::once(bin).chain(path.iter().copied()).collect();
+        crate::help::usage_line(&names, leaf_of(levels), &inherited_globals(levels))
+    };
+    #[cfg(not(feature = "help"))]
+    let usage = {
+        let _ = levels;
+        String::new()
+    };
+    (command_names(bin, path), usage)
+}
+
+fn unexpected_positional<Ctx: ?Sized>(bin: &str, path: &[&str], levels: &[&CmdSpec<'_, Ctx>], tok: &OsStr) -> Error {
+    use crate::spec::PosCardinality;
+    let max = leaf_of(levels).get_positionals().iter().fold(0usize, |n, p| match p.get_cardinality() {
+        PosCardinality::One { .. } => n.saturating_add(1),
+        PosCardinality::Range { max, .. } => n.saturating_add(max),
+        PosCardinality::Many => usize::MAX,
+    });
+    let (cmd_path, usage) = usage_context(bin, path, levels);
+    Error::UnexpectedPositional { token: os_dbg(tok), cmd_path, max, usage }
+}
+
+/// Validation errors of one parse: the first one stops parsing unless `Env::collect_errors` is set.
+struct Errors {
+    collect: bool,
+    list: Vec<Error>,
+    /// Command path of the level being validated, recorded on its errors.
+    cmd_path: Vec<String>,
+}
+
+impl Errors {
+    const fn new(env: &Env) -> Self {
+        Self { collect: env.collect_errors, list: Vec::new(), cmd_path: Vec::new() }
+    }
+
+    /// Validate the level at `path` next.
+    fn enter(&mut self, bin: &str, path: &[&str]) {
+        self.cmd_path = command_names(bin, path);
+    }
+
+    /// Record the error of `res`; it is returned (stopping validation) only when not collecting.
+    fn check(&mut self, res: Result<()>) -> Result<()> {
+        match res.map_err(|e| e.in_command(&self.cmd_path)) {
+            Err(e) if self.collect => {
+                self.list.push(e);
+                Ok(())
+            }
+            res => res,
+        }
+    }
+
+    /// A single collected error is returned as is, several as `Error::Multiple`.
+    fn finish(mut self) -> Result<()> {
+        match self.list.len() {
+            0 => Ok(()),
+            1 => Err(self.list.remove(0)),
+            _ => Err(Error::Multiple(self.list)),
+        }
+    }
+}
+
+/// Fill unset options of one level in precedence order: ENV, then config, then defaults.
+fn overlay_sources<Ctx: ?Sized>(
+    errs: &mut Errors,
+    m: &mut Matches,
+    path: &[&str],
+    cmd: &CmdSpec<'_, Ctx>,
+    config: Option<&dyn ConfigProvider>,
+) -> Result<()> {
+    errs.check(eager_overlay(m, path, cmd, config, Source::Env))?;
+    errs.check(eager_overlay(m, path, cmd, config, Source::Config))?;
+    errs.check(eager_overlay(m, path, cmd, config, Source::Default))
 }
 
-fn validate_level<'a, Ctx: ?Sized>(m: &Matches, path: &[&'a str], cmd: &CmdSpec<'a, Ctx>) -> Result<()> {
+fn validate_level<'a, Ctx: ?Sized>(
+    env: &Env,
+    errs: &mut Errors,
+    bin: &str,
+    config: Option<&dyn ConfigProvider>,
+    m: &Matches,
+    path: &[&'a str],
+    levels: &[&'a CmdSpec<'a, Ctx>],
+) -> Result<()> {
     use crate::spec::PosCardinality;
     use crate::Value;
+    let cmd = leaf_of(levels);
+
+    // Required options, after ENV/config/default overlays; every missing one is reported
+    let missing: Vec<String> = cmd
+        .get_opts()
+        .iter()
+        .filter(|o| o.is_required() && !matches!(m.status.get(&key_for(path, o.get_name())), Some(Status::Set(_))))
+        .map(crate::OptSpec::label)
+        .collect();
+    if !missing.is_empty() {
+        errs.check(Err(Error::MissingRequiredOptions { opts: missing, cmd_path: Vec::new() }))?;
+    }
 
     // Positionals: required + Range{min} check
     for p in cmd.get_positionals() {
         let k = pos_key_for(path, p.get_name());
         if p.get_cardinality() == (PosCardinality::One { required: true }) && !m.values.contains_key(&k) {
-            return Err(Error::User("missing required positional".into()));
+            let (cmd_path, usage) = usage_context(bin, path, levels);
+            let name = format!("<{}>", p.get_name());
+            errs.check(Err(Error::MissingPositional { name, cmd_path, usage }))?;
         }
         if let PosCardinality::Range { min, .. } = p.get_cardinality() {
             let count = match m.values.get(&k) {
@@ -445,38 +847,47 @@ fn validate_level<'a, Ctx: ?Sized>(m: &Matches, path: &[&'a str], cmd: &CmdSpec<
                 _ => 0,
             };
             if count < min {
-                return Err(Error::User("positional count below minimum".into()));
+                let (cmd_path, usage) = usage_context(bin, path, levels);
+                let name = format!("<{}>", p.get_name());
+                errs.check(Err(Error::TooFewPositionals { name, min, got: count, cmd_path, usage }))?;
             }
         }
     }
 
-    // Groups: Xor/ReqOne like in your code
+    // Groups
     for g in cmd.get_groups() {
-        let mut hits = 0u32;
-        for o in cmd.get_opts() {
-            if o.get_group() == Some(g.name) && m.status.contains_key(&key_for(path, o.get_name())) {
-                hits += 1;
-            }
-        }
-        match g.mode {
-            GroupMode::Xor if hits > 1 => {
-                return Err(Error::User(format!("options in group '{}' are mutually exclusive", g.name)))
-            }
-            GroupMode::ReqOne if hits == 0 => {
-                return Err(Error::User(format!("one of the options in group '{}' is required", g.name)))
-            }
-            _ => {}
-        }
+        errs.check(check_group(m, path, cmd, g))?;
     }
 
-    // Option validators
+    // Relations between arguments
+    for o in cmd.get_opts() {
+        errs.check(check_relations(m, path, levels, o))?;
+    }
+
+    // Choices
+    for o in cmd.get_opts() {
+        errs.check(check_choices(env, m, &key_for(path, o.get_name()), o.get_choices(), || o.label()))?;
+    }
+    for p in cmd.get_positionals() {
+        let label = || format!("<{}>", p.get_name());
+        errs.check(check_choices(env, m, &pos_key_for(path, p.get_name()), p.get_choices(), label))?;
+    }
+
+    // Option validators (errors for config values name the file and key)
     for o in cmd.get_opts() {
         if let Some(vf) = o.get_validator() {
-            match m.values.get(&key_for(path, o.get_name())) {
-                Some(Value::One(v)) => vf(v.as_os_str())?,
+            let k = key_for(path, o.get_name());
+            let check = |v: &OsString| match (vf(v.as_os_str()), config) {
+                (Err(e), Some(c)) if m.status.get(&k) == Some(&Status::Set(Source::Config)) => {
+                    Err(Error::User(format!("{}: {e}", c.origin(&k))))
+                }
+                (res, _) => res,
+            };
+            match m.values.get(&k) {
+                Some(Value::One(v)) => errs.check(check(v))?,
                 Some(Value::Many(vs)) => {
                     for v in vs {
-                        vf(v.as_os_str())?;
+                        errs.check(check(v))?;
                     }
                 }
                 _ => {}
@@ -488,10 +899,10 @@ fn validate_level<'a, Ctx: ?Sized>(m: &Matches, path: &[&'a str], cmd: &CmdSpec<
     for p in cmd.get_positionals() {
         if let Some(vf) = p.get_validator() {
             match m.values.get(&pos_key_for(path, p.get_name())) {
-                Some(Value::One(v)) => vf(v.as_os_str())?,
+                Some(Value::One(v)) => errs.check(vf(v.as_os_str()))?,
                 Some(Value::Many(vs)) => {
                     for v in vs {
-                        vf(v.as_os_str())?;
+                        errs.check(vf(v.as_os_str()))?;
                     }
                 }
                 _ => {}
@@ -501,12 +912,177 @@ fn validate_level<'a, Ctx: ?Sized>(m: &Matches, path: &[&'a str], cmd: &CmdSpec<
 
     // Command-level validator
     if let Some(cv) = cmd.get_validator() {
-        cv(m)?;
+        errs.check(cv(m))?;
     }
 
     Ok(())
 }
 
+/// Enforce one group rule. Members are listed by their CLI spelling (`--json`, `<FILE>`).
+fn check_group<Ctx: ?Sized>(
+    m: &Matches,
+    path: &[&str],
+    cmd: &CmdSpec<'_, Ctx>,
+    g: &crate::GroupDecl<'_>,
+) -> Result<()> {
+    // (label, explicitly given, has a value)
+    let mut members: Vec<(String, bool, bool)> = Vec::new();
+    for o in cmd.get_opts().iter().filter(|o| o.get_group() == Some(g.name)) {
+        let k = key_for(path, o.get_name());
+        members.push((o.label(), is_explicit(m, &k), has_value(m, &k)));
+    }
+    for p in cmd.get_positionals().iter().filter(|p| p.get_group() == Some(g.name)) {
+        let present = m.values.contains_key(&pos_key_for(path, p.get_name()));
+        members.push((format!("<{}>", p.get_name()), present, present));
+    }
+    let given = members.iter().filter(|x| x.1).count();
+    let valued = members.iter().filter(|x| x.2).count();
+    let broken = match g.mode {
+        GroupMode::Xor => given > 1,
+        GroupMode::ExactlyOne => given > 1 || valued == 0,
+        GroupMode::ReqOne => valued == 0,
+        GroupMode::AllOrNone => given > 0 && valued < members.len(),
+        GroupMode::AtLeast(n) => valued < n,
+        GroupMode::AtMost(n) => given > n,
+    };
+    if !broken {
+        return Ok(());
+    }
+    let labels = |f: fn(&(String, bool, bool)) -> bool| members.iter().filter(|x| f(x)).map(|x| x.0.clone()).collect();
+    Err(Error::GroupViolation {
+        group: g.name.to_string(),
+        mode: g.mode,
+        given: labels(|x| x.1),
+        missing: labels(|x| !x.2),
+        cmd_path: Vec::new(),
+    })
+}
+
+/// Check `requires`, `conflicts_with`, `required_unless` and `required_if` of `o`, declared on the leaf
+/// of `levels`. Triggers and conflicts only count explicitly given arguments (CLI/ENV/config); a default
+/// satisfies a requirement. A name that refers to no argument is a spec error, whether the rule fires or not.
+fn check_relations<'a, Ctx: ?Sized>(
+    m: &Matches,
+    path: &[&'a str],
+    levels: &[&'a CmdSpec<'a, Ctx>],
+    o: &crate::OptSpec<'_, Ctx>,
+) -> Result<()> {
+    let refs = |names: &[&str]| names.iter().map(|n| arg_ref(path, levels, o, n)).collect::<Result<Vec<_>>>();
+    let requires = refs(o.get_requires())?;
+    let conflicts = refs(o.get_conflicts_with())?;
+    let unless = refs(o.get_required_unless())?;
+    let required_if =
+        o.get_required_if().iter().map(|(n, v)| Ok((arg_ref(path, levels, o, n)?, *v))).collect::<Result<Vec<_>>>()?;
+    let key = key_for(path, o.get_name());
+    if is_explicit(m, &key) {
+        for (k, label) in requires {
+            if !has_value(m, &k) {
+                let reason = format!("required by '{}'", o.label());
+                return Err(Error::MissingRequired { arg: label, reason, cmd_path: Vec::new() });
+            }
+        }
+        for (k, label) in conflicts {
+            if is_explicit(m, &k) {
+                return Err(Error::ArgumentConflict { arg: o.label(), other: label, cmd_path: Vec::new() });
+            }
+        }
+    }
+    if has_value(m, &key) {
+        return Ok(());
+    }
+    if !unless.is_empty() && !unless.iter().any(|(k, _)| is_explicit(m, k)) {
+        let others: Vec<String> = unless.iter().map(|(_, label)| format!("'{label}'")).collect();
+        let reason = format!("required unless {} is given", others.join(" or "));
+        return Err(Error::MissingRequired { arg: o.label(), reason, cmd_path: Vec::new() });
+    }
+    for ((k, label), value) in required_if {
+        let hit = match m.values.get(&k) {
+            Some(Value::One(v)) => v == value,
+            Some(Value::Many(vs)) => vs.iter().any(|v| v == value),
+            _ => false,
+        };
+        if hit {
+            let reason = format!("required when '{label}' is '{value}'");
+            return Err(Error::MissingRequired { arg: o.label(), reason, cmd_path: Vec::new() });
+        }
+    }
+    Ok(())
+}
+
+/// Internal key and user-facing spelling of `name`, referenced by a relation of `o`: an option or
+/// positional of the leaf of `levels`, else a `global` option of the nearest ancestor declaring it (keyed
+/// under that ancestor's path). Unknown names are an `Error::Parse`.
+fn arg_ref<'a, Ctx: ?Sized>(
+    path: &[&str],
+    levels: &[&'a CmdSpec<'a, Ctx>],
+    o: &crate::OptSpec<'_, Ctx>,
+    name: &str,
+) -> Result<(String, String)> {
+    let cmd = leaf_of(levels);
+    if let Some(other) = cmd.get_opts().iter().find(|x| x.get_name() == name) {
+        return Ok((key_for(path, name), other.label()));
+    }
+    if cmd.get_positionals().iter().any(|p| p.get_name() == name) {
+        return Ok((pos_key_for(path, name), format!("<{name}>")));
+    }
+    for depth in (0..levels.len() - 1).rev() {
+        if let Some(other) = levels[depth].get_opts().iter().find(|x| x.get_name() == name && x.is_global()) {
+            return Ok((key_for(&path[..depth], name), other.label()));
+        }
+    }
+    Err(Error::Parse(format!("'{}' refers to unknown argument '{name}'", o.label())))
+}
+
+/// Set to a value or flag (from any source); a negated flag does not count.
+fn has_value(m: &Matches, key: &str) -> bool {
+    m.status.contains_key(key) && !matches!(m.values.get(key), Some(Value::Off))
+}
+
+/// Given on the CLI, in ENV or config (not a default) and not negated.
+fn is_explicit(m: &Matches, key: &str) -> bool {
+    has_value(m, key) && m.status.get(key) != Some(&Status::Set(Source::Default))
+}
+
+/// Reject any value stored under `key` that is not in `allowed` (no-op when `allowed` is empty).
+fn check_choices(env: &Env, m: &Matches, key: &str, allowed: &[&str], label: impl Fn() -> String) -> Result<()> {
+    use crate::Value;
+    if allowed.is_empty() {
+        return Ok(());
+    }
+    let values: &[OsString] = match m.values.get(key) {
+        Some(Value::One(v)) => core::slice::from_ref(v),
+        Some(Value::Many(vs)) => vs,
+        _ => &[],
+    };
+    for v in values {
+        let s = v.to_string_lossy();
+        if !allowed.contains(&s.as_ref()) {
+            let allowed: Vec<String> = allowed.iter().map(|a| (*a).to_string()).collect();
+            return Err(Error::InvalidChoice {
+                opt: label(),
+                suggestions: choice_suggestions(env, &s, &allowed),
+                value: s.into_owned(),
+                allowed,
+                cmd_path: Vec::new(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "suggest")]
+fn choice_suggestions(env: &Env, value: &str, allowed: &[String]) -> Vec<String> {
+    if env.suggest {
+        best_suggestions(value, allowed)
+    } else {
+        vec![]
+    }
+}
+#[cfg(not(feature = "suggest"))]
+fn choice_suggestions(_: &Env, _: &str, _: &[String]) -> Vec<String> {
+    vec![]
+}
+
 fn run_callbacks<'a, Ctx: ?Sized>(m: &Matches, path: &[&'a str], cmd: &CmdSpec<'a, Ctx>, ctx: &mut Ctx) -> Result<()> {
     use crate::Value;
 
@@ -534,7 +1110,7 @@ fn run_callbacks<'a, Ctx: ?Sized>(m: &Matches, path: &[&'a str], cmd: &CmdSpec<'
                     }
                 }
             }
-            None => {}
+            Some(Value::Off) | None => {}
         }
     }
 
@@ -559,8 +1135,8 @@ struct ParseCursor<'a, Ctx: ?Sized> {
     path: Vec<&'a str>,
     stack: Vec<&'a CmdSpec<'a, Ctx>>,
     current: &'a CmdSpec<'a, Ctx>,
-    long_ix: HashMap<&'a str, usize>,
-    short_ix: HashMap<char, usize>,
+    long_ix: HashMap<&'a str, OptLoc>,
+    short_ix: HashMap<char, OptLoc>,
     positional_only: bool,
     pos_idx: usize,
     pos_counts: Vec<usize>,
@@ -578,11 +1154,11 @@ impl<'a, Ctx: ?Sized> ParseCursor<'a, Ctx> {
             pos_idx: 0,
             pos_counts: vec![0; root.get_positionals().len()],
         };
-        rebuild_indexes(cur.current, &mut cur.long_ix, &mut cur.short_ix);
+        cur.rebuild_indexes();
         cur
     }
     fn rebuild_indexes(&mut self) {
-        rebuild_indexes(self.current, &mut self.long_ix, &mut self.short_ix);
+        rebuild_indexes(&self.stack, &mut self.long_ix, &mut self.short_ix);
     }
     fn descend(&mut self, sub: &'a CmdSpec<'a, Ctx>) {
         self.stack.push(sub);
@@ -593,8 +1169,214 @@ impl<'a, Ctx: ?Sized> ParseCursor<'a, Ctx> {
         self.pos_counts = vec![0; self.current.get_positionals().len()];
         self.rebuild_indexes();
     }
+    #[cfg(feature = "completions")]
+    fn opt_at(&self, (depth, idx): OptLoc) -> &'a crate::OptSpec<'a, Ctx> {
+        &self.stack[depth].get_opts()[idx]
+    }
+    /// Errors are reported later by `overlay_sources`, so `--help` still works with a bad ENV value.
     fn eager_overlay_here(&self, m: &mut Matches) {
-        eager_overlay(m, &self.path, self.current, Source::Env);
-        eager_overlay(m, &self.path, self.current, Source::Default);
+        let config = self.stack[0].get_config();
+        for src in [Source::Env, Source::Config, Source::Default] {
+            let _ = eager_overlay(m, &self.path, self.current, config, src);
+        }
+    }
+}
+
+/// Hidden first argument that turns `parse` into a completion query when `Env::dynamic_completions` is set.
+///
+/// Protocol: `tool __complete <INDEX> <WORDS>...` where `INDEX` is the position (within `WORDS`) of
+/// the word being completed; a missing word at `INDEX` is treated as empty. Candidates are returned
+/// as `Error::ExitMsg`, one per line, as `value` or `value<TAB>description`.
+#[cfg(feature = "completions")]
+pub const COMPLETE_COMMAND: &str = "__complete";
+
+#[cfg(feature = "completions")]
+fn complete_request<Ctx: ?Sized>(env: &Env, root: &CmdSpec<'_, Ctx>, args: &[OsString]) -> Error {
+    let Some(index) = args.first().and_then(|a| a.to_str()).and_then(|a| a.parse::<usize>().ok()) else {
+        return Error::User(format!("{COMPLETE_COMMAND} expects a word index"));
+    };
+    let words = &args[1..];
+    if index > words.len() {
+        return Error::User(format!("{COMPLETE_COMMAND}: index {index} is out of range"));
+    }
+    let lines: Vec<String> = complete(env, root, words, index)
+        .into_iter()
+        .map(|c| match c.help {
+            Some(h) => format!("{}\t{h}", c.value),
+            None => c.value,
+        })
+        .collect();
+    Error::ExitMsg { code: 0, message: Some(lines.join("\n")) }
+}
+
+/// Compute completion candidates for `words[index]`.
+///
+/// The preceding words are walked with the same rules as `parse` (subcommands, globals, value
+/// consumption, `--`). Options, built-ins, subcommands and `choices` are filtered by prefix;
+/// candidates from `completer` callbacks are returned as produced.
+#[cfg(feature = "completions")]
+#[must_use]
+pub fn complete<'a, Ctx: ?Sized>(
+    env: &Env,
+    root: &'a CmdSpec<'a, Ctx>,
+    words: &[OsString],
+    index: usize,
+) -> Vec<crate::Candidate> {
+    let mut cursor = ParseCursor::new(root);
+    let partial = words.get(index).map(|w| w.to_string_lossy().into_owned()).unwrap_or_default();
+    if let Some(loc) = complete_walk(&mut cursor, words, index) {
+        let o = cursor.opt_at(loc);
+        return value_candidates(o.get_completer(), o.get_choices(), &partial, "");
+    }
+
+    let mut out = Vec::new();
+    if !cursor.positional_only {
+        if let Some((name, value)) = partial.strip_prefix("--").and_then(|b| b.split_once('=')) {
+            if let Some(&loc) = cursor.long_ix.get(name) {
+                let o = cursor.opt_at(loc);
+                return value_candidates(o.get_completer(), o.get_choices(), value, &format!("--{name}="));
+            }
+            return out;
+        }
+        if partial.starts_with('-') {
+            return option_candidates(env, &cursor, &partial);
+        }
+        for sc in cursor.current.get_subcommands() {
+            for name in core::iter::once(sc.get_name()).chain(sc.get_aliases().iter().copied()) {
+                if name.starts_with(partial.as_str()) {
+                    out.push(with_help(crate::Candidate::new(name), sc.get_help()));
+                }
+            }
+        }
+    }
+    if let Some(p) = cursor.current.get_positionals().get(cursor.pos_idx) {
+        out.extend(value_candidates(p.get_completer(), p.get_choices(), &partial, ""));
+    }
+    out
+}
+
+/// Advance `cursor` over `words[..index]`. Returns the option whose value is being completed when
+/// `words[index]` is the detached value of a value-taking option.
+#[cfg(feature = "completions")]
+fn complete_walk<Ctx: ?Sized>(cursor: &mut ParseCursor<'_, Ctx>, words: &[OsString], index: usize) -> Option<OptLoc> {
+    let mut i = 0usize;
+    while i < index.min(words.len()) {
+        let tok = &words[i];
+        i += 1;
+        if !cursor.positional_only {
+            let s = tok.to_str().unwrap_or("");
+            if s == "--" {
+                cursor.positional_only = true;
+                continue;
+            }
+            if let Some(sub) = cursor.current.find_sub(s) {
+                cursor.descend(sub);
+                continue;
+            }
+            if let Some(body) = s.strip_prefix("--") {
+                if let Some(&loc) = cursor.long_ix.get(body) {
+                    if takes_detached_value(cursor.opt_at(loc)) {
+                        if i == index {
+                            return Some(loc);
+                        }
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            if let Some(rest) = s.strip_prefix('-') {
+                let numeric = rest.chars().next().map_or(true, |c| !cursor.short_ix.contains_key(&c))
+                    && looks_like_number_token(s);
+                if !rest.is_empty() && !numeric {
+                    let mut chars = rest.chars();
+                    while let Some(c) = chars.next() {
+                        if let Some(&loc) = cursor.short_ix.get(&c).filter(|&&l| cursor.opt_at(l).is_value()) {
+                            if !takes_detached_value(cursor.opt_at(loc)) {
+                                break;
+                            }
+                            if chars.as_str().is_empty() {
+                                if i == index {
+                                    return Some(loc);
+                                }
+                                i += 1;
+                            }
+                            break;
+                        }
+                    }
+                    continue;
+                }
+            }
+        }
+        if let Some(p) = cursor.current.get_positionals().get(cursor.pos_idx) {
+            advance_positional(p.get_cardinality(), &mut cursor.pos_idx, &mut cursor.pos_counts);
+        }
+    }
+    None
+}
+
+/// Whether the option consumes the next word as its value (required, not optional, values).
+#[cfg(feature = "completions")]
+fn takes_detached_value<Ctx: ?Sized>(o: &crate::OptSpec<'_, Ctx>) -> bool {
+    o.is_value() && !o.is_optional_value()
+}
+
+/// Built-ins plus every option visible at the cursor (own first, then inherited globals).
+#[cfg(feature = "completions")]
+fn option_candidates<Ctx: ?Sized>(env: &Env, cursor: &ParseCursor<'_, Ctx>, partial: &str) -> Vec<crate::Candidate> {
+    use crate::Candidate;
+    let mut out = Vec::new();
+    if env.auto_help {
+        out.push(Candidate::new("--help").help("Show this help and exit"));
+        out.push(Candidate::new("-h").help("Show this help and exit"));
+    }
+    if cursor.stack.len() == 1 {
+        if env.version.is_some() {
+            out.push(Candidate::new("--version").help("Show version and exit"));
+        }
+        if env.author.is_some() {
+            out.push(Candidate::new("--author").help("Show author and exit"));
+        }
+    }
+    for depth in (0..cursor.stack.len()).rev() {
+        for (idx, o) in cursor.stack[depth].get_opts().iter().enumerate() {
+            if let Some(l) = o.get_long().filter(|l| cursor.long_ix.get(l) == Some(&(depth, idx))) {
+                out.push(with_help(Candidate::new(format!("--{l}")), o.get_help()));
+                if o.is_negatable() {
+                    out.push(Candidate::new(format!("--no-{l}")));
+                }
+            }
+            if let Some(c) = o.get_short().filter(|c| cursor.short_ix.get(c) == Some(&(depth, idx))) {
+                out.push(with_help(Candidate::new(format!("-{c}")), o.get_help()));
+            }
+        }
+    }
+    out.retain(|c| c.value.starts_with(partial));
+    out
+}
+
+#[cfg(feature = "completions")]
+fn with_help(c: crate::Candidate, help: Option<&str>) -> crate::Candidate {
+    match help {
+        Some(h) => c.help(h),
+        None => c,
+    }
+}
+
+#[cfg(feature = "completions")]
+fn value_candidates(
+    completer: Option<&crate::CompleterFn<'_>>,
+    choices: &[&str],
+    partial: &str,
+    prefix: &str,
+) -> Vec<crate::Candidate> {
+    let mut out = completer.map_or_else(
+        || choices.iter().filter(|c| c.starts_with(partial)).map(|c| crate::Candidate::new(*c)).collect(),
+        |f| f(partial),
+    );
+    if !prefix.is_empty() {
+        for c in &mut out {
+            c.value.insert_str(0, prefix);
+        }
     }
+    out
 }
@@ -0,0 +1,109 @@
+//! `@path` response files (enabled with `Env::response_files`).
+//!
+//! A response file holds arguments separated by whitespace. Single quotes keep text literally,
+//! double quotes allow `\"` and `\\`, a backslash outside quotes escapes the next character, and
+//! `#` at the start of a word comments out the rest of the line. Nested `@path` words are resolved
+//! relative to the including file and expanded recursively; cycles are rejected.
+
+use crate::{Error, Result};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// One argument after expansion, with the response file it came from (if any).
+pub type Arg = (OsString, Option<Arc<Path>>);
+
+/// Expand every `@path` word of `argv` before the first `--`.
+pub fn expand(argv: &[OsString]) -> Result<Vec<Arg>> {
+    let mut out = Vec::with_capacity(argv.len());
+    let mut stack = Vec::new();
+    let mut literal = false;
+    for a in argv {
+        if let Some(p) = a.to_str().and_then(|s| s.strip_prefix('@')).filter(|p| !p.is_empty() && !literal) {
+            expand_file(Path::new(p), &mut stack, &mut out)?;
+        } else {
+            literal |= a == "--";
+            out.push((a.clone(), None));
+        }
+    }
+    Ok(out)
+}
+
+fn expand_file(path: &Path, stack: &mut Vec<PathBuf>, out: &mut Vec<Arg>) -> Result<()> {
+    let shown = path.display();
+    let real = path.canonicalize().map_err(|e| Error::User(format!("cannot read response file '{shown}': {e}")))?;
+    if stack.contains(&real) {
+        return Err(Error::User(format!("response file '{shown}' includes itself")));
+    }
+    let bytes = std::fs::read(&real).map_err(|e| Error::User(format!("cannot read response file '{shown}': {e}")))?;
+    let text =
+        String::from_utf8(bytes).map_err(|_| Error::User(format!("response file '{shown}' is not valid UTF-8")))?;
+    let origin: Arc<Path> = Arc::from(path);
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    stack.push(real);
+    for (word, line) in split_words(&text)
+        .map_err(|line| Error::User(format!("{shown}:{line}: unterminated quote in response file")))?
+    {
+        match word.strip_prefix('@').filter(|p| !p.is_empty()) {
+            Some(p) => {
+                expand_file(&dir.join(p), stack, out).map_err(|e| Error::User(format!("{shown}:{line}: {e}")))?;
+            }
+            None => out.push((OsString::from(word), Some(Arc::clone(&origin)))),
+        }
+    }
+    stack.pop();
+    Ok(())
+}
+
+/// Split response-file text into `(word, line)` pairs. On an unterminated quote, returns the line
+/// where the quote was opened.
+fn split_words(text: &str) -> core::result::Result<Vec<(String, usize)>, usize> {
+    let mut words = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            line += usize::from(c == '\n');
+            chars.next();
+            continue;
+        }
+        if c == '#' {
+            while chars.next_if(|&c| c != '\n').is_some() {}
+            continue;
+        }
+        let start = line;
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            match c {
+                '\'' | '"' => {
+                    let open = line;
+                    loop {
+                        match chars.next() {
+                            None => return Err(open),
+                            Some(q) if q == c => break,
+                            Some('\\') if c == '"' && matches!(chars.peek(), Some('"' | '\\')) => {
+                                word.extend(chars.next());
+                            }
+                            Some(ch) => {
+                                line += usize::from(ch == '\n');
+                                word.push(ch);
+                            }
+                        }
+                    }
+                }
+                '\\' => match chars.next() {
+                    Some('\n') => line += 1,
+                    Some(ch) => word.push(ch),
+                    None => {}
+                },
+                _ => word.push(c),
+            }
+        }
+        words.push((word, start));
+    }
+    Ok(words)
+}
@@ -3,28 +3,97 @@ use std::ffi::{OsStr, OsString};
 /// Color mode for help rendering.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColorMode {
+    /// Color when stdout is a terminal, unless the environment says otherwise (see [`ColorMode::enabled`]).
     Auto,
     Always,
     Never,
 }
 
+impl ColorMode {
+    /// Whether to emit ANSI styles, reading the process environment and whether stdout is a terminal.
+    #[must_use]
+    pub fn enabled(self) -> bool {
+        self.enabled_with(|k| std::env::var_os(k), is_terminal(false))
+    }
+
+    /// Like [`ColorMode::enabled`], for output written to stderr (error reports).
+    #[must_use]
+    pub fn enabled_on_stderr(self) -> bool {
+        self.enabled_with(|k| std::env::var_os(k), is_terminal(true))
+    }
+
+    /// Decide with an explicit variable lookup and terminal state. `Always`/`Never` are final; `Auto` is off
+    /// with a non-empty `NO_COLOR`, on with `CLICOLOR_FORCE` other than `0`, off with `CLICOLOR=0` or
+    /// `TERM=dumb`, and otherwise follows `is_tty`.
+    #[must_use]
+    pub fn enabled_with(self, var: impl Fn(&str) -> Option<OsString>, is_tty: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                let set = |k: &str| var(k).filter(|v| !v.is_empty());
+                if set("NO_COLOR").is_some() {
+                    false
+                } else if set("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+                    true
+                } else if set("CLICOLOR").is_some_and(|v| v == "0") || set("TERM").is_some_and(|v| v == "dumb") {
+                    false
+                } else {
+                    is_tty
+                }
+            }
+        }
+    }
+}
+
+/// Whether stdout (or stderr) is a terminal.
+fn is_terminal(stderr: bool) -> bool {
+    use std::io::IsTerminal;
+    if stderr {
+        std::io::stderr().is_terminal()
+    } else {
+        std::io::stdout().is_terminal()
+    }
+}
+
 /// Global environment for a parse/render session.
 #[derive(Clone, Copy, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Env {
     /// Wrap columns for help. `0` means no wrapping.
     pub wrap_cols: usize,
-    /// Whether to colorize help (honors `NO_COLOR` when `color` feature is enabled).
+    /// Whether to colorize help (`color` feature); `Auto` honors `NO_COLOR`, `CLICOLOR[_FORCE]` and `TERM=dumb`.
     pub color: ColorMode,
+    /// Styles for help and error rendering when colors are enabled.
+    pub styles: crate::Styles,
     /// Whether to compute suggestions on errors (if enabled).
     pub suggest: bool,
     /// Built-ins
     pub auto_help: bool,
     pub version: Option<&'static str>,
     pub author: Option<&'static str>,
+    /// Expand `@path` arguments (before `--`) into the arguments read from that file.
+    pub response_files: bool,
+    /// Report every validation error (as `Error::Multiple`) instead of stopping at the first.
+    pub collect_errors: bool,
+    /// Answer the hidden `__complete` query used by the dynamic completion glue (`completions` feature).
+    /// Off by default, so `__complete` stays an ordinary argument.
+    pub dynamic_completions: bool,
 }
 impl Default for Env {
     fn default() -> Self {
-        Self { wrap_cols: 0, color: ColorMode::Auto, suggest: true, auto_help: true, version: None, author: None }
+        Self {
+            wrap_cols: 0,
+            color: ColorMode::Auto,
+            styles: crate::Styles::DEFAULT,
+            suggest: true,
+            auto_help: true,
+            version: None,
+            author: None,
+            response_files: false,
+            collect_errors: false,
+            dynamic_completions: false,
+        }
     }
 }
 
@@ -35,11 +104,38 @@ pub enum Repeat {
     Many,
 }
 
-/// Group rule (applies to a set of options sharing the same group name).
+/// Group rule (applies to the options and positionals sharing the same group name).
+///
+/// Conflicts only count explicitly given members (CLI, ENV or config); a default satisfies a requirement.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GroupMode {
+    /// At most one member.
     Xor,
+    /// At least one member.
     ReqOne,
+    /// Exactly one member (`Xor` and `ReqOne` combined).
+    ExactlyOne,
+    /// Either every member or none of them (e.g. `--user` / `--password`).
+    AllOrNone,
+    /// At least `n` members.
+    AtLeast(usize),
+    /// At most `n` members.
+    AtMost(usize),
+}
+
+impl GroupMode {
+    /// Short description used in help, e.g. `exactly one of`.
+    #[must_use]
+    pub fn describe(self) -> String {
+        match self {
+            Self::Xor => "at most one of".into(),
+            Self::ReqOne => "at least one of".into(),
+            Self::ExactlyOne => "exactly one of".into(),
+            Self::AllOrNone => "all or none of".into(),
+            Self::AtLeast(n) => format!("at least {n} of"),
+            Self::AtMost(n) => format!("at most {n} of"),
+        }
+    }
 }
 
 /// Provenance of a value in `Matches`.
@@ -47,6 +143,8 @@ pub enum GroupMode {
 pub enum Source {
     Cli,
     Env,
+    /// From the root command's [`crate::ConfigProvider`].
+    Config,
     Default,
 }
 
@@ -62,25 +160,61 @@ pub type CmdValidatorFn<'a> = dyn Fn(&crate::Matches) -> crate::Result<()> + 'a;
 /// Command handler (executed for the **leaf** command after callbacks).
 pub type CmdHandlerFn<'a, Ctx> = dyn Fn(&crate::Matches, &mut Ctx) -> crate::Result<()> + 'a;
 
+/// A completion candidate: the value to insert plus an optional description.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub value: String,
+    pub help: Option<String>,
+}
+impl Candidate {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self { value: value.into(), help: None }
+    }
+    #[must_use]
+    pub fn help(mut self, h: impl Into<String>) -> Self {
+        self.help = Some(h.into());
+        self
+    }
+}
+
+/// Dynamic value completer: receives the partial word and returns candidates.
+pub type CompleterFn<'a> = dyn Fn(&str) -> Vec<Candidate> + 'a;
+
 /// Callback to apply a value/flag into user context.
 pub type OnValueFn<'a, Ctx> = dyn Fn(&OsStr, &mut Ctx) -> crate::Result<()> + 'a;
 pub type OnFlagFn<'a, Ctx> = dyn Fn(&mut Ctx) -> crate::Result<()> + 'a;
 
 /// Option (flag or value-bearing).
+#[allow(clippy::struct_excessive_bools)]
 pub struct OptSpec<'a, Ctx: ?Sized> {
     name: &'a str,
     short: Option<char>,
     long: Option<&'a str>,
     metavar: Option<&'a str>,
     help: Option<&'a str>,
+    long_help: Option<&'a str>,
     env: Option<&'a str>,
     default: Option<OsString>,
+    missing_value: Option<OsString>,
+    num_args: Option<(usize, usize)>,
+    delimiter: Option<char>,
+    env_delimiter: Option<char>,
+    requires: Vec<&'a str>,
+    conflicts_with: Vec<&'a str>,
+    required_unless: Vec<&'a str>,
+    required_if: Vec<(&'a str, &'a str)>,
     group: Option<&'a str>,
     repeat: Repeat,
     takes_value: bool,
+    global: bool,
+    required: bool,
+    negatable: bool,
+    count_from_env: bool,
     on_value: Option<Box<OnValueFn<'a, Ctx>>>, // value setter
     on_flag: Option<Box<OnFlagFn<'a, Ctx>>>,   // flag setter
     validator: Option<Box<ValueValidatorFn<'a>>>,
+    choices: &'a [&'a str],
+    completer: Option<Box<CompleterFn<'a>>>,
 }
 
 impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
@@ -95,17 +229,32 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
             long: None,
             metavar: None,
             help: None,
+            long_help: None,
             env: None,
             default: None,
+            missing_value: None,
+            num_args: None,
+            delimiter: None,
+            env_delimiter: None,
+            requires: Vec::new(),
+            conflicts_with: Vec::new(),
+            required_unless: Vec::new(),
+            required_if: Vec::new(),
             group: None,
             repeat: Repeat::Single,
             takes_value: false,
+            global: false,
+            required: false,
+            negatable: false,
+            count_from_env: false,
             on_value: None,
             on_flag: Some(Box::new(move |ctx| {
                 cb(ctx);
                 Ok(())
             })),
             validator: None,
+            choices: &[],
+            completer: None,
         }
     }
 
@@ -121,14 +270,29 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
             long: None,
             metavar: None,
             help: None,
+            long_help: None,
             env: None,
             default: None,
+            missing_value: None,
+            num_args: None,
+            delimiter: None,
+            env_delimiter: None,
+            requires: Vec::new(),
+            conflicts_with: Vec::new(),
+            required_unless: Vec::new(),
+            required_if: Vec::new(),
             group: None,
             repeat: Repeat::Single,
             takes_value: false,
+            global: false,
+            required: false,
+            negatable: false,
+            count_from_env: false,
             on_value: None,
             on_flag: Some(Box::new(move |ctx| cb(ctx).map_err(crate::Error::user))),
             validator: None,
+            choices: &[],
+            completer: None,
         }
     }
 
@@ -143,17 +307,32 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
             long: None,
             metavar: None,
             help: None,
+            long_help: None,
             env: None,
             default: None,
+            missing_value: None,
+            num_args: None,
+            delimiter: None,
+            env_delimiter: None,
+            requires: Vec::new(),
+            conflicts_with: Vec::new(),
+            required_unless: Vec::new(),
+            required_if: Vec::new(),
             group: None,
             repeat: Repeat::Single,
             takes_value: true,
+            global: false,
+            required: false,
+            negatable: false,
+            count_from_env: false,
             on_value: Some(Box::new(move |v, ctx| {
                 cb(v, ctx);
                 Ok(())
             })),
             on_flag: None,
             validator: None,
+            choices: &[],
+            completer: None,
         }
     }
 
@@ -169,14 +348,29 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
             long: None,
             metavar: None,
             help: None,
+            long_help: None,
             env: None,
             default: None,
+            missing_value: None,
+            num_args: None,
+            delimiter: None,
+            env_delimiter: None,
+            requires: Vec::new(),
+            conflicts_with: Vec::new(),
+            required_unless: Vec::new(),
+            required_if: Vec::new(),
             group: None,
             repeat: Repeat::Single,
             takes_value: true,
+            global: false,
+            required: false,
+            negatable: false,
+            count_from_env: false,
             on_value: Some(Box::new(move |v, ctx| cb(v, ctx).map_err(crate::Error::user))),
             on_flag: None,
             validator: None,
+            choices: &[],
+            completer: None,
         }
     }
 
@@ -201,6 +395,13 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
         self.help = Some(h);
         self
     }
+    /// Longer description for `--help`; `-h` shows `help`. Blank lines separate paragraphs and indented lines
+    /// are kept as written.
+    #[must_use]
+    pub fn long_help(mut self, h: &'a str) -> Self {
+        self.long_help = Some(h);
+        self
+    }
     #[must_use]
     pub fn env(mut self, name: &'a str) -> Self {
         self.env = Some(name);
@@ -211,6 +412,64 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
         self.default = Some(val.into());
         self
     }
+    /// Make the value **optional** (`--color[=WHEN]`): a bare `--color` / `-c` stores `missing`, while
+    /// `--color=never` / `-cnever` / `-c=never` store the attached value. The next argument is never consumed.
+    #[must_use]
+    pub fn optional_value(mut self, missing: impl Into<OsString>) -> Self {
+        self.missing_value = Some(missing.into());
+        self
+    }
+    /// Consume between `min` and `max` values per occurrence (`usize::MAX` for no limit), e.g.
+    /// `--resize 800 600`. Values stop at the next option-looking token or `--`.
+    #[must_use]
+    pub fn num_args(mut self, min: usize, max: usize) -> Self {
+        self.num_args = Some((min, max.max(min)));
+        self
+    }
+    /// Split each value on `sep` (`--plugin a,b` gives two values); also applies to ENV and defaults.
+    #[must_use]
+    pub fn delimiter(mut self, sep: char) -> Self {
+        self.delimiter = Some(sep);
+        self
+    }
+    /// Split ENV values on `sep` instead of `delimiter` (e.g. `:` for `PATH`-like variables).
+    #[must_use]
+    pub fn env_delimiter(mut self, sep: char) -> Self {
+        self.env_delimiter = Some(sep);
+        self
+    }
+    /// When given, the option or positional `name` of the same command (or an inherited `global` option)
+    /// must have a value too.
+    #[must_use]
+    pub fn requires(mut self, name: &'a str) -> Self {
+        self.requires.push(name);
+        self
+    }
+    /// Reject this option together with the option or positional `name` of the same command (or an
+    /// inherited `global` option).
+    #[must_use]
+    pub fn conflicts_with(mut self, name: &'a str) -> Self {
+        self.conflicts_with.push(name);
+        self
+    }
+    /// The option must be given on the CLI or by ENV, config or default; shown as `--name <VAL>` in usage.
+    #[must_use]
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+    /// Require this option unless `name` is given (several calls: unless any of them is given).
+    #[must_use]
+    pub fn required_unless(mut self, name: &'a str) -> Self {
+        self.required_unless.push(name);
+        self
+    }
+    /// Require this option when the option `name` has the value `value` (e.g. `--auth=token`).
+    #[must_use]
+    pub fn required_if(mut self, name: &'a str, value: &'a str) -> Self {
+        self.required_if.push((name, value));
+        self
+    }
     #[must_use]
     pub fn group(mut self, g: &'a str) -> Self {
         self.group = Some(g);
@@ -226,6 +485,29 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
         self.repeat = Repeat::Many;
         self
     }
+    /// Make the option **global**: it is also accepted by every subcommand below the declaring command.
+    /// Values are stored under the declaring command's scope.
+    #[must_use]
+    pub fn global(mut self) -> Self {
+        self.global = true;
+        self
+    }
+
+    /// Also accept `--no-<long>` on a flag: it records the flag as explicitly off (`Source::Cli`),
+    /// overriding ENV/default, and its callback is not run. The last occurrence wins.
+    #[must_use]
+    pub fn negatable(mut self) -> Self {
+        self.negatable = true;
+        self
+    }
+
+    /// Read the flag's `env` variable as an occurrence count (`VERBOSE=3` → `flag_count == 3`);
+    /// boolean spellings are still accepted.
+    #[must_use]
+    pub fn count_from_env(mut self) -> Self {
+        self.count_from_env = true;
+        self
+    }
 
     /// Value validator that returns a displayable error (converted into `Error::User`).
     #[must_use]
@@ -249,6 +531,23 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
         self
     }
 
+    /// Restrict values to `allowed`; anything else is `Error::InvalidChoice`. Listed in help and completions.
+    #[must_use]
+    pub fn choices(mut self, allowed: &'a [&'a str]) -> Self {
+        self.choices = allowed;
+        self
+    }
+
+    /// Dynamic value completer used by the `__complete` protocol (`completions` feature).
+    #[must_use]
+    pub fn completer<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> Vec<Candidate> + 'a,
+    {
+        self.completer = Some(Box::new(f));
+        self
+    }
+
     // --- getters (get_*; booleans use is_*) ---
     #[must_use]
     pub fn get_name(&self) -> &str {
@@ -262,6 +561,14 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
     pub fn get_long(&self) -> Option<&str> {
         self.long
     }
+    /// User-facing spelling in diagnostics and group help: `--long`, else `-s`, else its name.
+    pub(crate) fn label(&self) -> String {
+        match (self.long, self.short) {
+            (Some(l), _) => format!("--{l}"),
+            (None, Some(c)) => format!("-{c}"),
+            (None, None) => self.name.to_string(),
+        }
+    }
     #[must_use]
     pub fn get_metavar(&self) -> Option<&str> {
         self.metavar
@@ -271,6 +578,10 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
         self.help
     }
     #[must_use]
+    pub fn get_long_help(&self) -> Option<&str> {
+        self.long_help
+    }
+    #[must_use]
     pub fn get_env(&self) -> Option<&str> {
         self.env
     }
@@ -286,6 +597,61 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
     pub fn is_value(&self) -> bool {
         self.takes_value
     }
+    /// Value stored for a bare `--name` when the value is optional (see `optional_value`).
+    #[must_use]
+    pub fn get_missing_value(&self) -> Option<&OsStr> {
+        self.missing_value.as_deref().filter(|_| self.takes_value)
+    }
+    #[must_use]
+    pub fn is_optional_value(&self) -> bool {
+        self.get_missing_value().is_some()
+    }
+    /// Values per occurrence as `(min, max)`, when set with `num_args`.
+    #[must_use]
+    pub fn get_num_args(&self) -> Option<(usize, usize)> {
+        self.num_args.filter(|_| self.takes_value)
+    }
+    #[must_use]
+    pub fn get_delimiter(&self) -> Option<char> {
+        self.delimiter
+    }
+    #[must_use]
+    pub fn get_requires(&self) -> &[&'a str] {
+        &self.requires
+    }
+    #[must_use]
+    pub fn get_conflicts_with(&self) -> &[&'a str] {
+        &self.conflicts_with
+    }
+    #[must_use]
+    pub fn get_required_unless(&self) -> &[&'a str] {
+        &self.required_unless
+    }
+    #[must_use]
+    pub fn get_required_if(&self) -> &[(&'a str, &'a str)] {
+        &self.required_if
+    }
+    /// Separator for ENV values: `env_delimiter`, else `delimiter`.
+    #[must_use]
+    pub fn get_env_delimiter(&self) -> Option<char> {
+        self.env_delimiter.or(self.delimiter)
+    }
+    #[must_use]
+    pub fn is_global(&self) -> bool {
+        self.global
+    }
+    #[must_use]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+    #[must_use]
+    pub fn is_count_from_env(&self) -> bool {
+        self.count_from_env
+    }
+    #[must_use]
+    pub fn is_negatable(&self) -> bool {
+        self.negatable && !self.takes_value && self.long.is_some()
+    }
     #[must_use]
     pub fn get_repeat(&self) -> Repeat {
         self.repeat
@@ -302,6 +668,14 @@ impl<'a, Ctx: ?Sized> OptSpec<'a, Ctx> {
     pub fn get_validator(&self) -> Option<&ValueValidatorFn<'a>> {
         self.validator.as_deref()
     }
+    #[must_use]
+    pub fn get_choices(&self) -> &[&'a str] {
+        self.choices
+    }
+    #[must_use]
+    pub fn get_completer(&self) -> Option<&CompleterFn<'a>> {
+        self.completer.as_deref()
+    }
 }
 
 /// Positional cardinality.
@@ -316,9 +690,13 @@ pub enum PosCardinality {
 pub struct PosSpec<'a, Ctx: ?Sized> {
     name: &'a str,
     help: Option<&'a str>,
+    long_help: Option<&'a str>,
     card: PosCardinality,
     on_value: Box<OnValueFn<'a, Ctx>>,
     validator: Option<Box<ValueValidatorFn<'a>>>,
+    choices: &'a [&'a str],
+    group: Option<&'a str>,
+    completer: Option<Box<CompleterFn<'a>>>,
 }
 impl<'a, Ctx: ?Sized> PosSpec<'a, Ctx> {
     pub fn new<F>(name: &'a str, cb: F) -> Self
@@ -328,12 +706,16 @@ impl<'a, Ctx: ?Sized> PosSpec<'a, Ctx> {
         Self {
             name,
             help: None,
+            long_help: None,
             card: PosCardinality::One { required: false },
             on_value: Box::new(move |v, ctx| {
                 cb(v, ctx);
                 Ok(())
             }),
             validator: None,
+            choices: &[],
+            group: None,
+            completer: None,
         }
     }
 
@@ -345,9 +727,13 @@ impl<'a, Ctx: ?Sized> PosSpec<'a, Ctx> {
         Self {
             name,
             help: None,
+            long_help: None,
             card: PosCardinality::One { required: false },
             on_value: Box::new(move |v, ctx| cb(v, ctx).map_err(crate::Error::user)),
             validator: None,
+            choices: &[],
+            group: None,
+            completer: None,
         }
     }
 
@@ -357,6 +743,13 @@ impl<'a, Ctx: ?Sized> PosSpec<'a, Ctx> {
         self.help = Some(h);
         self
     }
+    /// Longer description for `--help`; `-h` shows `help`. Blank lines separate paragraphs and indented lines
+    /// are kept as written.
+    #[must_use]
+    pub fn long_help(mut self, h: &'a str) -> Self {
+        self.long_help = Some(h);
+        self
+    }
     #[must_use]
     pub fn required(mut self) -> Self {
         self.card = PosCardinality::One { required: true };
@@ -395,6 +788,29 @@ impl<'a, Ctx: ?Sized> PosSpec<'a, Ctx> {
         self
     }
 
+    /// Restrict values to `allowed`; anything else is `Error::InvalidChoice`. Listed in help and completions.
+    #[must_use]
+    pub fn choices(mut self, allowed: &'a [&'a str]) -> Self {
+        self.choices = allowed;
+        self
+    }
+    /// Put this positional in a group declared with `CmdSpec::group` (e.g. "either `--stdin` or FILE").
+    #[must_use]
+    pub fn group(mut self, name: &'a str) -> Self {
+        self.group = Some(name);
+        self
+    }
+
+    /// Dynamic value completer used by the `__complete` protocol (`completions` feature).
+    #[must_use]
+    pub fn completer<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> Vec<Candidate> + 'a,
+    {
+        self.completer = Some(Box::new(f));
+        self
+    }
+
     // getters
     #[must_use]
     pub fn get_name(&self) -> &str {
@@ -405,6 +821,10 @@ impl<'a, Ctx: ?Sized> PosSpec<'a, Ctx> {
         self.help
     }
     #[must_use]
+    pub fn get_long_help(&self) -> Option<&str> {
+        self.long_help
+    }
+    #[must_use]
     pub fn get_cardinality(&self) -> PosCardinality {
         self.card
     }
@@ -424,6 +844,18 @@ impl<'a, Ctx: ?Sized> PosSpec<'a, Ctx> {
     pub fn get_validator(&self) -> Option<&ValueValidatorFn<'a>> {
         self.validator.as_deref()
     }
+    #[must_use]
+    pub fn get_choices(&self) -> &[&'a str] {
+        self.choices
+    }
+    #[must_use]
+    pub fn get_group(&self) -> Option<&str> {
+        self.group
+    }
+    #[must_use]
+    pub fn get_completer(&self) -> Option<&CompleterFn<'a>> {
+        self.completer.as_deref()
+    }
 }
 
 /// Group declaration.
@@ -436,13 +868,15 @@ pub struct GroupDecl<'a> {
 pub struct CmdSpec<'a, Ctx: ?Sized> {
     name: &'a str,
     help: Option<&'a str>,
+    long_about: Option<&'a str>,
     aliases: Vec<&'a str>,
     opts: Vec<OptSpec<'a, Ctx>>,
     positionals: Vec<PosSpec<'a, Ctx>>,
-    subcommands: Vec<CmdSpec<'a, Ctx>>,
+    subcommands: Vec<Self>,
     groups: Vec<GroupDecl<'a>>,
     validate_cmd: Option<Box<CmdValidatorFn<'a>>>,
     handler: Option<Box<CmdHandlerFn<'a, Ctx>>>, // leaf command handler
+    config: Option<Box<dyn crate::ConfigProvider + 'a>>,
 }
 impl<'a, Ctx: ?Sized> CmdSpec<'a, Ctx> {
     #[must_use]
@@ -450,6 +884,7 @@ impl<'a, Ctx: ?Sized> CmdSpec<'a, Ctx> {
         Self {
             name,
             help: None,
+            long_about: None,
             aliases: Vec::new(),
             opts: Vec::new(),
             positionals: Vec::new(),
@@ -457,6 +892,7 @@ impl<'a, Ctx: ?Sized> CmdSpec<'a, Ctx> {
             groups: Vec::new(),
             validate_cmd: None,
             handler: None,
+            config: None,
         }
     }
     // builders
@@ -465,6 +901,12 @@ impl<'a, Ctx: ?Sized> CmdSpec<'a, Ctx> {
         self.help = Some(s);
         self
     }
+    /// Longer description shown at the top of `--help` (and in man pages) instead of `help`.
+    #[must_use]
+    pub fn long_about(mut self, s: &'a str) -> Self {
+        self.long_about = Some(s);
+        self
+    }
     #[must_use]
     pub fn alias(mut self, a: &'a str) -> Self {
         self.aliases.push(a);
@@ -513,6 +955,14 @@ impl<'a, Ctx: ?Sized> CmdSpec<'a, Ctx> {
         self
     }
 
+    /// Configuration layer between ENV and defaults. Only the **root** command's provider is used;
+    /// it is queried with scoped keys (`remote.add.fetch`) for options at every level.
+    #[must_use]
+    pub fn config(mut self, provider: impl crate::ConfigProvider + 'a) -> Self {
+        self.config = Some(Box::new(provider));
+        self
+    }
+
     /// Set a leaf command handler. Only the **selected leaf** handler is executed.
     #[must_use]
     pub fn handler<F>(mut self, cb: F) -> Self
@@ -547,6 +997,10 @@ impl<'a, Ctx: ?Sized> CmdSpec<'a, Ctx> {
         self.help
     }
     #[must_use]
+    pub fn get_long_about(&self) -> Option<&str> {
+        self.long_about
+    }
+    #[must_use]
     pub fn get_aliases(&self) -> &[&'a str] {
         &self.aliases
     }
@@ -575,7 +1029,11 @@ impl<'a, Ctx: ?Sized> CmdSpec<'a, Ctx> {
         self.handler.as_deref()
     }
     #[must_use]
+    pub fn get_config(&self) -> Option<&(dyn crate::ConfigProvider + 'a)> {
+        self.config.as_deref()
+    }
+    #[must_use]
     pub fn find_sub(&self, needle: &str) -> Option<&Self> {
-        self.subcommands.iter().find(|c| c.name == needle || c.aliases.iter().any(|a| *a == needle))
+        self.subcommands.iter().find(|c| c.name == needle || c.aliases.contains(&needle))
     }
 }
@@ -0,0 +1,210 @@
+use crate::{Error, Result};
+
+/// One text style: the SGR parameters of an ANSI escape (`1;4;37` is bold, underlined, white).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    codes: [u8; Self::MAX_CODES],
+    len: u8,
+}
+
+impl Style {
+    /// Most SGR parameters a style holds (enough for `38;2;r;g;b` plus attributes).
+    pub const MAX_CODES: usize = 10;
+    /// No styling; text is written as is.
+    pub const PLAIN: Self = Self { codes: [0; Self::MAX_CODES], len: 0 };
+
+    /// Style from SGR parameters; parameters past [`Style::MAX_CODES`] are dropped.
+    #[must_use]
+    pub const fn new(codes: &[u8]) -> Self {
+        let mut out = Self::PLAIN;
+        while (out.len as usize) < codes.len() && (out.len as usize) < Self::MAX_CODES {
+            out.codes[out.len as usize] = codes[out.len as usize];
+            out.len += 1;
+        }
+        out
+    }
+
+    /// Parse `1;4;37`-style SGR parameters; an empty string is [`Style::PLAIN`].
+    ///
+    /// # Errors
+    /// `Error::User` for a parameter that is not a number in `0..=255` or too many parameters.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut out = Self::PLAIN;
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let code = part.trim().parse::<u8>().map_err(|_| Error::User(format!("invalid style code '{part}'")))?;
+            if out.len as usize == Self::MAX_CODES {
+                return Err(Error::User(format!("style '{s}' has more than {} codes", Self::MAX_CODES)));
+            }
+            out.codes[out.len as usize] = code;
+            out.len += 1;
+        }
+        Ok(out)
+    }
+
+    #[must_use]
+    pub fn get_codes(&self) -> &[u8] {
+        &self.codes[..self.len as usize]
+    }
+    #[must_use]
+    pub const fn is_plain(&self) -> bool {
+        self.len == 0
+    }
+
+    /// `s` wrapped in this style's escape and a reset (unchanged when plain).
+    #[must_use]
+    pub fn paint(&self, s: &str) -> String {
+        if self.is_plain() {
+            s.to_string()
+        } else {
+            format!("{self}{s}\x1b[0m")
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::PLAIN
+    }
+}
+
+/// The escape sequence that starts this style (`\x1b[1;4;37m`); nothing when plain.
+impl core::fmt::Display for Style {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_plain() {
+            return Ok(());
+        }
+        f.write_str("\x1b[")?;
+        for (i, c) in self.get_codes().iter().enumerate() {
+            if i > 0 {
+                f.write_str(";")?;
+            }
+            write!(f, "{c}")?;
+        }
+        f.write_str("m")
+    }
+}
+
+/// Styles used by help and error rendering (`Env::styles`); only applied when colors are enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Styles {
+    /// `Usage:` title.
+    pub title: Style,
+    /// Section headings (`Options:`, `Commands:`).
+    pub section: Style,
+    /// Option spellings (`-j, --jobs`).
+    pub option: Style,
+    /// Positional names.
+    pub positional: Style,
+    /// Option metavars (`N`, `WHEN`).
+    pub metavar: Style,
+    /// Subcommand names.
+    pub command: Style,
+    /// `error:` prefix.
+    pub error: Style,
+    /// `warning:` prefix.
+    pub warning: Style,
+    /// Text the user types as is: the program name, the offending token, `--help` hints.
+    pub literal: Style,
+    /// Placeholders such as `<FILE>` in messages.
+    pub placeholder: Style,
+}
+
+impl Styles {
+    /// The built-in colors.
+    pub const DEFAULT: Self = Self {
+        title: Style::new(&[1, 4, 37]),
+        section: Style::new(&[4, 37]),
+        option: Style::new(&[0, 94]),
+        positional: Style::new(&[0, 93]),
+        metavar: Style::new(&[0, 96]),
+        command: Style::new(&[0, 95]),
+        error: Style::new(&[1, 31]),
+        warning: Style::new(&[1, 33]),
+        literal: Style::new(&[0, 97, 1]),
+        placeholder: Style::new(&[0, 36]),
+    };
+    /// No styling at all, even when colors are enabled.
+    pub const PLAIN: Self = Self {
+        title: Style::PLAIN,
+        section: Style::PLAIN,
+        option: Style::PLAIN,
+        positional: Style::PLAIN,
+        metavar: Style::PLAIN,
+        command: Style::PLAIN,
+        error: Style::PLAIN,
+        warning: Style::PLAIN,
+        literal: Style::PLAIN,
+        placeholder: Style::PLAIN,
+    };
+    /// Bold, bright colors that stay readable on light and dark backgrounds.
+    pub const HIGH_CONTRAST: Self = Self {
+        title: Style::new(&[1, 4]),
+        section: Style::new(&[1, 4]),
+        option: Style::new(&[1, 34]),
+        positional: Style::new(&[1, 35]),
+        metavar: Style::new(&[1, 36]),
+        command: Style::new(&[1, 32]),
+        error: Style::new(&[1, 7, 31]),
+        warning: Style::new(&[1, 7, 33]),
+        literal: Style::new(&[1]),
+        placeholder: Style::new(&[1, 4, 36]),
+    };
+
+    /// Preset by name: `default`, `plain` or `high-contrast`.
+    #[must_use]
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::DEFAULT),
+            "plain" => Some(Self::PLAIN),
+            "high-contrast" => Some(Self::HIGH_CONTRAST),
+            _ => None,
+        }
+    }
+
+    /// Override styles from an `LS_COLORS`-like spec: `:`-separated `key=SGR` entries
+    /// (`option=1;34:metavar=36`), where a bare preset name replaces every style.
+    ///
+    /// # Errors
+    /// `Error::User` naming an unknown key or preset, or a malformed style.
+    pub fn with_spec(mut self, spec: &str) -> Result<Self> {
+        for entry in spec.split(':').map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((key, value)) = entry.split_once('=') else {
+                self = Self::preset(entry).ok_or_else(|| Error::User(format!("unknown style preset '{entry}'")))?;
+                continue;
+            };
+            let style = Style::parse(value)?;
+            let slot = match key.trim() {
+                "title" => &mut self.title,
+                "section" => &mut self.section,
+                "option" => &mut self.option,
+                "positional" => &mut self.positional,
+                "metavar" => &mut self.metavar,
+                "command" => &mut self.command,
+                "error" => &mut self.error,
+                "warning" => &mut self.warning,
+                "literal" => &mut self.literal,
+                "placeholder" => &mut self.placeholder,
+                other => return Err(Error::User(format!("unknown style key '{other}'"))),
+            };
+            *slot = style;
+        }
+        Ok(self)
+    }
+
+    /// [`Styles::DEFAULT`] overridden by the spec in the environment variable `var`, when set.
+    ///
+    /// # Errors
+    /// `Error::User` naming `var` when its value is not a valid spec.
+    pub fn from_env(var: &str) -> Result<Self> {
+        let Ok(spec) = std::env::var(var) else {
+            return Ok(Self::DEFAULT);
+        };
+        Self::DEFAULT.with_spec(&spec).map_err(|e| Error::User(format!("invalid {var}: {e}")))
+    }
+}
+
+impl Default for Styles {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
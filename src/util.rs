@@ -62,3 +62,14 @@ pub fn looks_like_number_token(s: &str) -> bool {
     }
     has_digit
 }
+
+/// Interpret a boolean spelling (case-insensitive, surrounding whitespace ignored):
+/// `1/true/yes/on` → `true`, `0/false/no/off` and the empty string → `false`.
+#[must_use]
+pub fn parse_bool(s: &str) -> Option<bool> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "" | "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
@@ -20,3 +20,64 @@ fn help_and_version_exit_zero() {
         _ => panic!("{err:?}"),
     }
 }
+
+#[test]
+fn exit_codes_follow_error_kind() {
+    let env = env_basic();
+    let root = ap::CmdSpec::new("t")
+        .opt(ap::OptSpec::value("jobs", set_jobs).long("jobs").validator(|_: &std::ffi::OsStr| Err("bad jobs")))
+        .pos(ap::PosSpec::new("FILE", push_file));
+    let code = |a: &[&str]| ap::parse(&env, &root, &argv(a), &mut Ctx::default()).unwrap_err().exit_code();
+
+    assert_eq!(code(&["--help"]), 0);
+    assert_eq!(code(&["--version"]), 0);
+    assert_eq!(code(&["--bogus"]), 2);
+    assert_eq!(code(&["--jobs"]), 2);
+    assert_eq!(code(&["a", "b"]), 2);
+    assert_eq!(code(&["--jobs", "x"]), 1);
+    assert_eq!(ap::Error::User("x".into()).exit_code_with(3), 3);
+    assert_eq!(ap::Error::Parse("internal".into()).exit_code(), 70);
+    assert_eq!(ap::Error::Multiple(vec![ap::Error::User("a".into()), ap::Error::Parse("b".into())]).exit_code(), 1);
+
+    // help text displays as is, without an "(exit 0)" suffix
+    let help = ap::parse(&env, &root, &argv(&["--version"]), &mut Ctx::default()).unwrap_err();
+    assert_eq!(help.to_string(), "0.1.0");
+
+    let exit = ap::Exit::from(ap::parse(&env, &root, &argv(&["--jobs", "x"]), &mut Ctx::default()));
+    assert_eq!(exit.get_code(), 1);
+    assert_eq!(exit.user_code(4).get_code(), 4);
+    let ok: ap::Exit = Ok::<_, ap::Error>(()).into();
+    assert!(ok.get_error().is_none() && ok.get_code() == 0);
+}
+
+#[test]
+fn run_from_routes_output_and_codes() {
+    let env = env_basic();
+    let root = ap::CmdSpec::new("t")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v'))
+        .pos(ap::PosSpec::new("FILE", push_file));
+    let run = |a: &[&str]| {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let mut ctx = Ctx::default();
+        let code = ap::run_from(&env, &root, &argv(a), &mut ctx, &mut out, &mut err);
+        (code, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap(), ctx)
+    };
+
+    let (code, out, err, ctx) = run(&["/usr/bin/tool", "-v", "a"]);
+    assert_eq!((code, out.as_str(), err.as_str(), ctx.verbose), (0, "", "", 1));
+
+    let (code, out, err, _) = run(&["/usr/bin/tool", "--help"]);
+    assert_eq!(code, 0);
+    assert!(out.contains("tool [options] [FILE]"), "{out}");
+    assert!(out.ends_with('\n') && !out.ends_with("\n\n"), "{out:?}");
+    assert!(err.is_empty());
+
+    let (code, out, _, _) = run(&["tool", "--version"]);
+    assert_eq!((code, out.as_str()), (0, "0.1.0\n"));
+
+    let (code, out, err, _) = run(&["./tool.exe", "a", "b"]);
+    assert_eq!(code, 2);
+    assert!(out.is_empty());
+    assert!(err.starts_with("error: unexpected positional 'b'"), "{err}");
+    assert!(err.contains("Usage: tool [options] [FILE]"), "{err}");
+}
@@ -0,0 +1,74 @@
+use rust_args_parser as ap;
+mod common;
+use common::*;
+
+fn spec<'a>() -> ap::CmdSpec<'a, Ctx> {
+    ap::CmdSpec::new("tool")
+        .opt(
+            ap::OptSpec::value("format", set_limit)
+                .short('f')
+                .long("format")
+                .help("Output format")
+                .env("TOOL_CHOICES_FORMAT")
+                .choices(&["json", "yaml", "table"]),
+        )
+        .pos(ap::PosSpec::new("MODE", set_input).choices(&["fast", "slow"]))
+}
+
+#[test]
+fn accepts_declared_choices() {
+    let env = env_basic();
+    let mut ctx = Ctx::default();
+    ap::parse(&env, &spec(), &argv(&["--format", "yaml", "slow"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("yaml"));
+    assert_eq!(ctx.input.as_deref(), Some(std::ffi::OsStr::new("slow")));
+}
+
+#[test]
+fn rejects_other_values_with_suggestions() {
+    let env = env_basic();
+    let mut ctx = Ctx::default();
+    let err = ap::parse(&env, &spec(), &argv(&["-f", "jsno"]), &mut ctx).unwrap_err();
+    match &err {
+        ap::Error::InvalidChoice { opt, value, allowed, suggestions, .. } => {
+            assert_eq!(opt, "--format");
+            assert_eq!(value, "jsno");
+            assert_eq!(allowed, &["json", "yaml", "table"]);
+            #[cfg(feature = "suggest")]
+            assert_eq!(suggestions, &["json"]);
+            #[cfg(not(feature = "suggest"))]
+            assert!(suggestions.is_empty());
+        }
+        _ => panic!("{err:?}"),
+    }
+    assert!(err.to_string().starts_with("invalid value for '--format': 'jsno' (possible: json, yaml, table)"));
+    #[cfg(feature = "suggest")]
+    assert_eq!(
+        err.to_string(),
+        "invalid value for '--format': 'jsno' (possible: json, yaml, table). Did you mean 'json'?"
+    );
+    assert!(ctx.limit.is_none(), "callbacks must not run");
+
+    let err = ap::parse(&env, &spec(), &argv(&["medium"]), &mut ctx).unwrap_err();
+    assert!(matches!(err, ap::Error::InvalidChoice { ref opt, .. } if opt == "<MODE>"), "{err:?}");
+}
+
+#[test]
+fn env_values_are_checked_too() {
+    std::env::set_var("TOOL_CHOICES_FORMAT", "xml");
+    let mut ctx = Ctx::default();
+    let res = ap::parse(&env_basic(), &spec(), &argv(&[]), &mut ctx);
+    std::env::remove_var("TOOL_CHOICES_FORMAT");
+    assert!(matches!(res, Err(ap::Error::InvalidChoice { .. })));
+}
+
+#[cfg(feature = "help")]
+#[test]
+fn help_lists_possible_values() {
+    let mut ctx = Ctx::default();
+    let env = ap::Env { wrap_cols: 200, ..env_basic() };
+    let err = ap::parse(&env, &spec(), &argv(&["--help"]), &mut ctx).unwrap_err();
+    let ap::Error::ExitMsg { message: Some(msg), .. } = err else { panic!("expected help") };
+    assert!(msg.contains("Output format; Env: TOOL_CHOICES_FORMAT [possible: json, yaml, table]"), "{msg}");
+    assert!(msg.contains("[possible: fast, slow]"), "{msg}");
+}
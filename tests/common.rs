@@ -13,10 +13,14 @@ pub const fn env_basic() -> ap::Env {
     ap::Env {
         wrap_cols: 80,
         color: ap::ColorMode::Never,
+        styles: ap::Styles::DEFAULT,
         suggest: true,
         auto_help: true,
         version: Some("0.1.0"),
         author: Some("Testy McTestface <t@example.com>"),
+        response_files: false,
+        collect_errors: false,
+        dynamic_completions: false,
     }
 }
 
@@ -0,0 +1,187 @@
+#![cfg(feature = "completions")]
+use rust_args_parser as ap;
+use std::path::PathBuf;
+mod common;
+use common::*;
+
+#[path = "../examples/git.rs"]
+#[allow(dead_code)]
+mod git;
+
+fn git_env() -> ap::Env {
+    ap::Env { version: Some("2.0.0"), author: Some("The Demo Team"), ..Default::default() }
+}
+
+/// Compare against `tests/snapshots/<name>`; set `UPDATE_SNAPSHOTS=1` to rewrite.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    pretty_assertions::assert_eq!(expected, actual);
+}
+
+#[test]
+fn git_bash_snapshot() {
+    let root = git::build_spec();
+    assert_snapshot("git.bash", &ap::render_completions(&git_env(), &root, ap::Shell::Bash, "git"));
+}
+
+#[test]
+fn git_zsh_snapshot() {
+    let root = git::build_spec();
+    assert_snapshot("git.zsh", &ap::render_completions(&git_env(), &root, ap::Shell::Zsh, "git"));
+}
+
+#[test]
+fn git_fish_snapshot() {
+    let root = git::build_spec();
+    assert_snapshot("git.fish", &ap::render_completions(&git_env(), &root, ap::Shell::Fish, "git"));
+}
+
+#[test]
+fn git_powershell_snapshot() {
+    let root = git::build_spec();
+    assert_snapshot("git.ps1", &ap::render_completions(&git_env(), &root, ap::Shell::PowerShell, "git"));
+}
+
+#[test]
+fn scripts_know_aliases_and_globals() {
+    let root = ap::CmdSpec::<Ctx>::new("tool")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v').long("verbose").global())
+        .subcmd(ap::CmdSpec::new("remote").alias("rmt").subcmd(ap::CmdSpec::new("add")));
+    let env = ap::Env::default();
+    for shell in [ap::Shell::Bash, ap::Shell::Zsh, ap::Shell::Fish, ap::Shell::PowerShell] {
+        let s = ap::render_completions(&env, &root, shell, "tool");
+        assert!(s.contains("rmt"), "{shell:?}");
+        assert!(s.contains("tool__remote__add"), "{shell:?}");
+        assert!(s.contains("verbose"), "{shell:?}");
+    }
+    assert_eq!("pwsh".parse::<ap::Shell>().unwrap(), ap::Shell::PowerShell);
+    assert!("tcsh".parse::<ap::Shell>().is_err());
+}
+
+#[test]
+fn choices_feed_static_and_dynamic_completion() {
+    let root = ap::CmdSpec::<Ctx>::new("tool")
+        .opt(ap::OptSpec::value("format", set_limit).long("format").choices(&["json", "yaml"]))
+        .pos(ap::PosSpec::new("MODE", set_input).choices(&["fast", "slow"]));
+    let env = ap::Env::default();
+    let bash = ap::render_completions(&env, &root, ap::Shell::Bash, "tool");
+    assert!(bash.contains("compgen -W \"json yaml\""), "{bash}");
+    assert!(bash.contains("fast slow"), "{bash}");
+    let zsh = ap::render_completions(&env, &root, ap::Shell::Zsh, "tool");
+    assert!(zsh.contains("--format=[]:VALUE:(json yaml)'"), "{zsh}");
+    assert!(zsh.contains("'1:MODE:(fast slow)'"), "{zsh}");
+    let fish = ap::render_completions(&env, &root, ap::Shell::Fish, "tool");
+    assert!(fish.contains("-l format -r -f -a 'json yaml'"), "{fish}");
+    let ps = ap::render_completions(&env, &root, ap::Shell::PowerShell, "tool");
+    assert!(ps.contains("'tool,--format' { 'json', 'yaml' }"), "{ps}");
+
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["--format", "y"]), 1)), vec!["yaml"]);
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["--format=j"]), 0)), vec!["--format=json"]);
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["f"]), 0)), vec!["fast"]);
+}
+
+#[test]
+fn negatable_flags_complete_both_spellings() {
+    let root = ap::CmdSpec::<Ctx>::new("tool").opt(ap::OptSpec::flag("color", set_json).long("color").negatable());
+    let env = ap::Env::default();
+    for shell in [ap::Shell::Bash, ap::Shell::Zsh, ap::Shell::Fish, ap::Shell::PowerShell] {
+        let s = ap::render_completions(&env, &root, shell, "tool");
+        assert!(s.contains("no-color"), "{shell:?}");
+    }
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["--no"]), 0)), vec!["--no-color"]);
+}
+
+#[test]
+fn optional_values_do_not_take_the_next_word() {
+    let root = ap::CmdSpec::<Ctx>::new("tool")
+        .opt(ap::OptSpec::value("color", set_limit).short('c').long("color").metavar("WHEN").optional_value("auto"))
+        .subcmd(ap::CmdSpec::new("remote"));
+    let env = ap::Env::default();
+    let zsh = ap::render_completions(&env, &root, ap::Shell::Zsh, "tool");
+    assert!(zsh.contains("--color=-[") && zsh.contains("::WHEN:"), "{zsh}");
+    // `--color` is complete on its own, so the next word is a subcommand
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["--color", "re"]), 1)), vec!["remote"]);
+}
+
+fn words(a: &[&str]) -> Vec<std::ffi::OsString> {
+    argv(a)
+}
+
+fn values(c: &[ap::Candidate]) -> Vec<&str> {
+    c.iter().map(|c| c.value.as_str()).collect()
+}
+
+fn dyn_spec<'a>() -> ap::CmdSpec<'a, Ctx> {
+    ap::CmdSpec::new("tool")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v').long("verbose").global())
+        .opt(ap::OptSpec::value("format", set_limit).short('f').long("format").help("Output format").completer(|p| {
+            ["json", "yaml"].iter().filter(|f| f.starts_with(p)).map(|f| ap::Candidate::new(*f)).collect()
+        }))
+        .subcmd(ap::CmdSpec::new("remote").help("Remotes").alias("rmt"))
+        .subcmd(
+            ap::CmdSpec::new("checkout").pos(
+                ap::PosSpec::new("BRANCH", set_input)
+                    .completer(|p| vec![ap::Candidate::new(format!("{p}main")).help("default branch")]),
+            ),
+        )
+}
+
+#[test]
+fn dynamic_completes_subcommands_options_and_values() {
+    let env = ap::Env::default();
+    let root = dyn_spec();
+
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["re"]), 0)), vec!["remote"]);
+    assert_eq!(values(&ap::complete(&env, &root, &[], 0)), vec!["remote", "rmt", "checkout"]);
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["--f"]), 0)), vec!["--format"]);
+    // detached, short and inline values go through the completer
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["--format", "j"]), 1)), vec!["json"]);
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["-vf"]), 1)), vec!["json", "yaml"]);
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["--format=y"]), 0)), vec!["--format=yaml"]);
+    // after descent: positional completer plus inherited global
+    let c = ap::complete(&env, &root, &words(&["-f", "json", "checkout", "dev/"]), 3);
+    assert_eq!(c, vec![ap::Candidate::new("dev/main").help("default branch")]);
+    assert_eq!(values(&ap::complete(&env, &root, &words(&["checkout", "--v"]), 1)), vec!["--verbose"]);
+}
+
+#[test]
+fn hidden_complete_command_prints_candidates() {
+    let root = dyn_spec();
+    // without the opt-in, `__complete` is an ordinary token
+    let err = ap::parse(&ap::Env::default(), &root, &argv(&["__complete", "0", "rem"]), &mut Ctx::default());
+    assert!(matches!(err, Err(ap::Error::UnknownCommand { .. })), "{err:?}");
+
+    let env = ap::Env { dynamic_completions: true, ..Default::default() };
+    let err = ap::parse(&env, &root, &argv(&["__complete", "1", "--format"]), &mut Ctx::default()).unwrap_err();
+    match err {
+        ap::Error::ExitMsg { code, message } => {
+            assert_eq!(code, 0);
+            assert_eq!(message.as_deref(), Some("json\nyaml"));
+        }
+        other => panic!("{other:?}"),
+    }
+    let err = ap::parse(&env, &root, &argv(&["__complete", "0", "rem"]), &mut Ctx::default()).unwrap_err();
+    let ap::Error::ExitMsg { message, .. } = err else { panic!("{err:?}") };
+    assert_eq!(message.as_deref(), Some("remote\tRemotes"));
+
+    let err = ap::parse(&env, &root, &argv(&["__complete", "x"]), &mut Ctx::default()).unwrap_err();
+    assert!(matches!(err, ap::Error::User(_)));
+}
+
+#[test]
+fn dynamic_glue_calls_back_into_binary() {
+    for shell in [ap::Shell::Bash, ap::Shell::Zsh, ap::Shell::Fish, ap::Shell::PowerShell] {
+        let s = ap::render_dynamic_completions(shell, "tool");
+        assert!(s.contains("__complete"), "{shell:?}");
+    } // bash re-splits the line itself so `--opt=value` stays one word
+    let bash = ap::render_dynamic_completions(ap::Shell::Bash, "tool");
+    assert!(
+        bash.contains("read -ra words <<< \"${COMP_LINE:0:COMP_POINT}\"") && !bash.contains("COMP_WORDS["),
+        "{bash}"
+    );
+}
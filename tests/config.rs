@@ -0,0 +1,113 @@
+use rust_args_parser as ap;
+use std::env;
+use std::ffi::OsString;
+mod common;
+use common::*;
+
+const INI: &str = "
+# defaults for the demo tool
+limit = 10
+jobs = \"4\"
+json = yes
+[remote.add]
+fetch = on
+";
+
+fn spec<'a>(cfg: ap::IniConfig) -> ap::CmdSpec<'a, Ctx> {
+    ap::CmdSpec::new("demo")
+        .config(cfg)
+        .opt(ap::OptSpec::value("limit", set_limit).long("limit").env("APP_CFG_LIMIT").default("1"))
+        .opt(
+            ap::OptSpec::value("jobs", set_jobs)
+                .long("jobs")
+                .validator(|v| v.to_string_lossy().parse::<u32>().map(|_| ()).map_err(|_| "jobs must be a number")),
+        )
+        .opt(ap::OptSpec::flag("json", set_json).long("json"))
+        .subcmd(
+            ap::CmdSpec::new("remote")
+                .subcmd(ap::CmdSpec::new("add").opt(ap::OptSpec::flag("fetch", inc_verbose).long("fetch"))),
+        )
+}
+
+#[test]
+fn config_sits_between_env_and_default() {
+    let cfg = ap::IniConfig::parse("app.ini", INI).unwrap();
+    let root = spec(cfg);
+
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env_basic(), &root, &argv(&["remote", "add"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("10"));
+    assert_eq!(ctx.jobs, Some(4));
+    assert!(ctx.json);
+    assert_eq!(ctx.verbose, 1, "scoped key remote.add.fetch");
+    assert!(m.view().is_set_from("fetch", ap::Source::Config));
+    assert!(m.at(&[]).is_set_from("limit", ap::Source::Config));
+
+    env::set_var("APP_CFG_LIMIT", "20");
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env_basic(), &root, &[], &mut ctx).unwrap();
+    env::remove_var("APP_CFG_LIMIT");
+    assert_eq!(ctx.limit.as_deref(), Some("20"));
+    assert!(m.is_set_from("limit", ap::Source::Env));
+
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env_basic(), &root, &argv(&["--limit", "30"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("30"));
+    assert!(m.is_set_from("limit", ap::Source::Cli));
+
+    // default when the config does not mention the key
+    let root = spec(ap::IniConfig::parse("empty.ini", "").unwrap());
+    let m = ap::parse(&env_basic(), &root, &[], &mut Ctx::default()).unwrap();
+    assert!(m.is_set_from("limit", ap::Source::Default));
+}
+
+#[test]
+fn config_errors_name_file_and_key() {
+    let root = spec(ap::IniConfig::parse("app.ini", "limit = 1\njobs = many\n").unwrap());
+    let err = ap::parse(&env_basic(), &root, &[], &mut Ctx::default()).unwrap_err();
+    assert_eq!(err.to_string(), "app.ini:2: key 'jobs': jobs must be a number");
+
+    // a CLI value is validated without the config prefix
+    let err = ap::parse(&env_basic(), &root, &argv(&["--jobs", "x"]), &mut Ctx::default()).unwrap_err();
+    assert_eq!(err.to_string(), "jobs must be a number");
+
+    let root = spec(ap::IniConfig::parse("app.ini", "json = maybe").unwrap());
+    let err = ap::parse(&env_basic(), &root, &[], &mut Ctx::default()).unwrap_err();
+    assert!(err.to_string().starts_with("app.ini:1: key 'json': invalid value 'maybe' for '--json'"), "{err}");
+
+    let err = ap::IniConfig::parse("bad.ini", "[remote\n").unwrap_err();
+    assert_eq!(err.to_string(), "bad.ini:1: unterminated section header");
+    let err = ap::IniConfig::parse("bad.ini", "ok = 1\njust words\n").unwrap_err();
+    assert_eq!(err.to_string(), "bad.ini:2: expected 'key = value'");
+}
+
+#[test]
+fn custom_provider_and_repeated_keys() {
+    struct Fixed;
+    impl ap::ConfigProvider for Fixed {
+        fn get(&self, key: &str) -> Vec<OsString> {
+            if key == "file" {
+                vec!["a".into(), "b".into()]
+            } else {
+                vec![]
+            }
+        }
+    }
+    let root =
+        ap::CmdSpec::new("demo").config(Fixed).opt(ap::OptSpec::value("file", push_file).long("file").repeatable());
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env_basic(), &root, &[], &mut ctx).unwrap();
+    assert_eq!(ctx.files, argv(&["a", "b"]));
+    assert!(m.is_set_from("file", ap::Source::Config));
+
+    let ini = ap::IniConfig::parse("x.ini", "file = a\nfile = 'b c'\n").unwrap();
+    assert_eq!(ap::ConfigProvider::get(&ini, "file"), argv(&["a", "b c"]));
+    assert_eq!(ap::ConfigProvider::origin(&ini, "file"), "x.ini:2: key 'file'");
+
+    // a single-valued option keeps the last value and runs its callback once
+    let root = ap::CmdSpec::new("demo").config(ini).opt(ap::OptSpec::value("file", push_file).long("file"));
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env_basic(), &root, &[], &mut ctx).unwrap();
+    assert_eq!(ctx.files, argv(&["b c"]));
+    assert_eq!(m.get_values("file"), Some(&argv(&["b c"])[..]));
+}
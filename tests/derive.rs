@@ -0,0 +1,213 @@
+#![cfg(feature = "derive")]
+mod common;
+use common::*;
+use rust_args_parser as ap;
+use rust_args_parser::Args as _;
+use std::path::PathBuf;
+
+/// Build things
+///
+/// Reads a manifest:
+///
+///     tool -j 4 --json
+#[derive(ap::Args, Default, Debug)]
+#[command(name = "tool")]
+struct Tool {
+    /// Worker threads
+    ///
+    /// One per core
+    /// by default.
+    #[arg(short, env = "TOOL_JOBS")]
+    jobs: Option<u32>,
+    /// Increase verbosity
+    #[arg(short, count, global)]
+    verbose: u8,
+    /// Emit JSON
+    json: bool,
+    /// Color mode
+    #[arg(choices = ["auto", "always", "never"])]
+    color: Option<String>,
+    /// Extra include dirs
+    #[arg(short = 'I', long = "include")]
+    includes: Vec<PathBuf>,
+    #[command(flatten)]
+    out: Output,
+    #[command(subcommand)]
+    cmd: Option<ToolCmd>,
+}
+
+#[derive(ap::Args, Default, Debug)]
+struct Output {
+    /// Output file
+    #[arg(short, metavar = "FILE", default = "out.txt")]
+    output: String,
+}
+
+#[derive(ap::Subcommands, Debug, PartialEq, Eq)]
+enum ToolCmd {
+    /// Manage remotes
+    Remote(Remote),
+    /// Print the version
+    #[command(name = "ver", alias = "v")]
+    Version,
+}
+
+#[derive(ap::Args, Default, Debug, PartialEq, Eq)]
+struct Remote {
+    #[command(subcommand)]
+    cmd: Option<RemoteCmd>,
+}
+
+#[derive(ap::Subcommands, Debug, PartialEq, Eq)]
+enum RemoteCmd {
+    /// Add a remote
+    Add(RemoteAdd),
+}
+
+#[derive(ap::Args, Default, Debug, PartialEq, Eq)]
+struct RemoteAdd {
+    /// Fetch after adding
+    fetch: bool,
+    /// Remote name
+    #[arg(positional)]
+    name: String,
+    /// Remote URLs
+    #[arg(positional)]
+    urls: Vec<String>,
+}
+
+#[test]
+fn derive_fills_fields_from_options() {
+    let t = Tool::parse_from(&env_basic(), &argv(&["-j", "4", "-vv", "--json", "-I", "a", "--include=b"])).unwrap();
+    assert_eq!(t.jobs, Some(4));
+    assert_eq!(t.verbose, 2);
+    assert!(t.json);
+    assert_eq!(t.includes, vec![PathBuf::from("a"), PathBuf::from("b")]);
+    assert_eq!(t.out.output, "out.txt");
+    assert!(t.cmd.is_none());
+}
+
+#[test]
+fn derive_nested_subcommands_and_positionals() {
+    let t = Tool::parse_from(&env_basic(), &argv(&["remote", "add", "--fetch", "-v", "origin", "u1", "u2"])).unwrap();
+    assert_eq!(t.verbose, 1);
+    let expected = RemoteAdd { fetch: true, name: "origin".into(), urls: vec!["u1".into(), "u2".into()] };
+    assert_eq!(t.cmd, Some(ToolCmd::Remote(Remote { cmd: Some(RemoteCmd::Add(expected)) })));
+}
+
+#[test]
+fn derive_unit_variant_with_alias_and_intermediate_leaf() {
+    let t = Tool::parse_from(&env_basic(), &argv(&["v"])).unwrap();
+    assert_eq!(t.cmd, Some(ToolCmd::Version));
+    let t = Tool::parse_from(&env_basic(), &argv(&["remote"])).unwrap();
+    assert_eq!(t.cmd, Some(ToolCmd::Remote(Remote { cmd: None })));
+}
+
+#[test]
+fn derive_invalid_value_is_user_error() {
+    let err = Tool::parse_from(&env_basic(), &argv(&["--jobs", "many"])).unwrap_err();
+    match err {
+        ap::Error::User(msg) => assert!(msg.contains("'many'") && msg.contains("--jobs"), "{msg}"),
+        other => panic!("unexpected: {other:?}"),
+    }
+    let err = Tool::parse_from(&env_basic(), &argv(&["remote", "add"])).unwrap_err();
+    assert!(matches!(err, ap::Error::MissingPositional { .. }), "{err:?}");
+}
+
+#[test]
+fn derive_choices() {
+    let t = Tool::parse_from(&env_basic(), &argv(&["--color", "never"])).unwrap();
+    assert_eq!(t.color.as_deref(), Some("never"));
+    let err = Tool::parse_from(&env_basic(), &argv(&["--color", "nevr"])).unwrap_err();
+    assert!(matches!(err, ap::Error::InvalidChoice { .. }), "{err:?}");
+}
+
+#[test]
+fn derive_doc_comments_become_help() {
+    let err = Tool::parse_from(&env_basic(), &argv(&["--help"])).unwrap_err();
+    let ap::Error::ExitMsg { message: Some(help), .. } = err else { panic!("expected help") };
+    assert!(help.starts_with("Build things"));
+    assert!(help.contains("--jobs"));
+    assert!(help.contains("Worker threads"));
+    assert!(help.contains("FILE"));
+    assert!(help.contains("Print the version"));
+    assert!(help.contains("Manage remotes"));
+
+    // `-h` shows first paragraphs only; `--help` the whole comments
+    let err = Tool::parse_from(&env_basic(), &argv(&["-h"])).unwrap_err();
+    let ap::Error::ExitMsg { message: Some(short), .. } = err else { panic!("expected help") };
+    assert!(short.starts_with("Build things\n\nUsage"), "{short}");
+    assert!(!short.contains("One per core"), "{short}");
+    assert!(help.starts_with("Build things\n\nReads a manifest:\n\n    tool -j 4 --json\n"), "{help}");
+    assert!(help.contains("Worker threads\n") && help.contains("One per core by default."), "{help}");
+}
+
+#[test]
+fn derive_env_overlay_goes_through_callbacks() {
+    std::env::set_var("TOOL_JOBS", "7");
+    let t = Tool::parse_from(&env_basic(), &argv(&[])).unwrap();
+    std::env::remove_var("TOOL_JOBS");
+    assert_eq!(t.jobs, Some(7));
+}
+
+#[derive(ap::Args, Default, Debug)]
+struct Clash {
+    /// Emit JSON
+    #[arg(short)]
+    json: bool,
+    #[command(flatten)]
+    more: Journal,
+}
+
+#[derive(ap::Args, Default, Debug)]
+struct Journal {
+    #[arg(short)]
+    journal: Option<String>,
+}
+
+#[test]
+#[should_panic(expected = "short option '-j' of '--journal' in 'clash' is already used by '--json'")]
+fn derive_rejects_short_clash_through_flatten() {
+    let _ = Clash::command();
+}
+
+#[derive(ap::Args, Default, Debug)]
+struct GlobalClash {
+    #[arg(short, global)]
+    quiet: bool,
+    #[command(subcommand)]
+    cmd: Option<GlobalClashCmd>,
+}
+
+#[derive(ap::Subcommands, Debug)]
+enum GlobalClashCmd {
+    Run(Query),
+}
+
+#[derive(ap::Args, Default, Debug)]
+struct Query {
+    #[arg(short)]
+    query: Option<String>,
+}
+
+#[test]
+#[should_panic(expected = "short option '-q' of '--query' in 'run' is already used by '--quiet'")]
+fn derive_rejects_short_clash_with_inherited_global() {
+    let _ = GlobalClash::command();
+}
+
+#[derive(ap::Args, Default, Debug)]
+struct Login {
+    user: String,
+    #[arg(default = "main")]
+    branch: String,
+    port: Option<u16>,
+}
+
+#[test]
+fn derive_plain_value_options_are_required() {
+    let err = Login::parse_from(&env_basic(), &argv(&[])).unwrap_err();
+    assert!(matches!(&err, ap::Error::MissingRequiredOptions { opts, .. } if opts == &["--user"]), "{err:?}");
+    let login = Login::parse_from(&env_basic(), &argv(&["--user", "me"])).unwrap();
+    assert_eq!((login.user.as_str(), login.branch.as_str(), login.port), ("me", "main", None));
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "docs")]
+use rust_args_parser as ap;
+mod common;
+use common::*;
+
+fn spec<'a>() -> ap::CmdSpec<'a, Ctx> {
+    ap::CmdSpec::new("tool")
+        .help("Multitool root")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v').long("verbose").help("Verbose").global())
+        .opt(
+            ap::OptSpec::value("format", set_limit)
+                .long("format")
+                .metavar("FMT")
+                .env("TOOL_FORMAT")
+                .default("table")
+                .group("out")
+                .help("Output | format"),
+        )
+        .subcmd(
+            ap::CmdSpec::new("remote").help("Manage remotes").alias("rmt").subcmd(
+                ap::CmdSpec::new("add")
+                    .help("Add a remote")
+                    .pos(ap::PosSpec::new("NAME", set_input).required().help("Remote name"))
+                    .pos(ap::PosSpec::new("URL", push_file).range(1, 3)),
+            ),
+        )
+}
+
+#[test]
+fn markdown_has_anchors_tables_and_cardinality() {
+    let md = ap::render_markdown(&env_basic(), &spec());
+    assert!(md.starts_with("# tool\n"));
+    assert!(md.contains("    - [tool remote add](#tool-remote-add)\n"));
+    assert!(md.contains("<a id=\"tool-remote\"></a>\n\n## tool remote\n"));
+    assert!(md.contains("**Aliases:** `rmt`"));
+    assert!(md.contains("**Usage:** `tool remote add [options] <NAME> <URL...>`"));
+    assert!(md.contains("| Short | Long | Value | Env | Default | Group | Description |"));
+    assert!(md.contains("|  | `--format` | `FMT` | `TOOL_FORMAT` | `table` | `out` | Output \\| format |"));
+    assert!(md.contains("### Inherited options"));
+    assert!(md.contains("| `NAME` | 1 (required) | Remote name |"));
+    assert!(md.contains("| `URL` | 1..3 |  |"));
+    assert!(md.contains("| [`remote`](#tool-remote) | `rmt` | Manage remotes |"));
+}
+
+#[test]
+fn unbounded_positionals_show_a_star() {
+    let root = ap::CmdSpec::new("cat")
+        .pos(ap::PosSpec::new("FILE", push_file).many())
+        .subcmd(ap::CmdSpec::new("join").pos(ap::PosSpec::new("PART", push_file).range(2, usize::MAX)));
+    let md = ap::render_markdown(&env_basic(), &root);
+    assert!(md.contains("| `FILE` | 0..* |  |"), "{md}");
+    assert!(md.contains("| `PART` | 2..* |  |"), "{md}");
+    assert!(!md.contains(&usize::MAX.to_string()), "{md}");
+}
+
+#[test]
+fn html_mirrors_markdown_structure() {
+    let html = ap::render_html(&env_basic(), &spec());
+    assert!(html.contains("<section id=\"tool-remote-add\">"));
+    assert!(html.contains("<code>tool remote add [options] &lt;NAME&gt; &lt;URL...&gt;</code>"));
+    assert!(html.contains("<td><code>TOOL_FORMAT</code></td>"));
+    assert!(html.contains("<a href=\"#tool-remote\"><code>remote</code></a>"));
+}
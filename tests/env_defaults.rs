@@ -43,3 +43,160 @@ fn env_defaults() {
     assert_eq!(env.version, None);
     assert_eq!(env.author, None);
 }
+
+#[test]
+fn negated_flag_overrides_env() {
+    let envv = env_basic();
+    let root = ap::CmdSpec::new("d")
+        .opt(ap::OptSpec::flag("json", set_json).long("json").env("APP_NEG_JSON").help("JSON output").negatable());
+
+    env::set_var("APP_NEG_JSON", "1");
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &argv(&["--no-json"]), &mut ctx).unwrap();
+    env::remove_var("APP_NEG_JSON");
+    assert!(!ctx.json, "callback must not run for --no-json");
+    assert!(m.is_set_from("json", ap::Source::Cli));
+    assert_eq!(m.get_flag("json"), Some(false));
+    assert_eq!(m.flag_count("json"), 0);
+
+    // last occurrence wins
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &argv(&["--no-json", "--json"]), &mut ctx).unwrap();
+    assert!(ctx.json);
+    assert_eq!(m.view().flag("json"), Some(true));
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &argv(&["--json", "--no-json"]), &mut ctx).unwrap();
+    assert!(!ctx.json);
+    assert_eq!(m.get_flag("json"), Some(false));
+
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &argv(&[]), &mut ctx).unwrap();
+    assert_eq!(m.get_flag("json"), None);
+    // flags take no inline value, negated or not
+    for tok in ["--no-json=yes", "--json=no"] {
+        let err = ap::parse(&envv, &root, &argv(&[tok]), &mut Ctx::default()).unwrap_err();
+        assert!(matches!(err, ap::Error::UnexpectedValue { .. }), "{tok}: {err:?}");
+        assert_eq!(err.exit_code(), 2);
+    }
+    let err = ap::parse(&envv, &root, &argv(&["--no-json=yes"]), &mut Ctx::default()).unwrap_err();
+    assert_eq!(err.to_string(), "flag '--no-json' takes no value, found 'yes'");
+}
+
+#[test]
+fn no_prefix_requires_negatable() {
+    let root = ap::CmdSpec::new("d").opt(ap::OptSpec::flag("json", set_json).long("json"));
+    let mut ctx = Ctx::default();
+    let err = ap::parse(&env_basic(), &root, &argv(&["--no-json"]), &mut ctx).unwrap_err();
+    assert!(matches!(err, ap::Error::UnknownOption { ref token, .. } if token == "--no-json"), "{err:?}");
+}
+
+#[test]
+fn flag_env_values_are_boolean_aware() {
+    let envv = env_basic();
+    let root = ap::CmdSpec::new("d").opt(ap::OptSpec::flag("json", set_json).long("json").env("APP_BOOL_JSON"));
+
+    for (raw, on) in [("1", true), ("TRUE", true), ("yes", true), ("on", true)] {
+        env::set_var("APP_BOOL_JSON", raw);
+        let mut ctx = Ctx::default();
+        let m = ap::parse(&envv, &root, &[], &mut ctx).unwrap();
+        assert_eq!(ctx.json, on, "{raw}");
+        assert_eq!(m.get_flag("json"), Some(on), "{raw}");
+    }
+    // a falsy value leaves a plain flag unset; a negatable one records an explicit "off"
+    let negatable =
+        ap::CmdSpec::new("d").opt(ap::OptSpec::flag("json", set_json).long("json").env("APP_BOOL_JSON").negatable());
+    for raw in ["0", "false", "No", "off", ""] {
+        env::set_var("APP_BOOL_JSON", raw);
+        let mut ctx = Ctx::default();
+        let m = ap::parse(&envv, &root, &[], &mut ctx).unwrap();
+        assert!(!ctx.json, "{raw:?}");
+        assert!(!m.is_set("json"), "{raw:?}");
+        assert!(!m.is_set_from("json", ap::Source::Env), "{raw:?}");
+        assert_eq!((m.get_flag("json"), m.flag_count("json")), (None, 0), "{raw:?}");
+
+        let m = ap::parse(&envv, &negatable, &[], &mut Ctx::default()).unwrap();
+        assert_eq!(m.get_flag("json"), Some(false), "{raw:?}");
+        assert!(m.is_set_from("json", ap::Source::Env), "{raw:?}");
+    }
+
+    env::set_var("APP_BOOL_JSON", "maybe");
+    let mut ctx = Ctx::default();
+    let err = ap::parse(&envv, &root, &[], &mut ctx).unwrap_err();
+    // CLI still wins over a bad ENV value, and built-ins keep working
+    let cli = ap::parse(&envv, &root, &argv(&["--json"]), &mut Ctx::default());
+    let help = ap::parse(&envv, &root, &argv(&["--help"]), &mut Ctx::default());
+    env::remove_var("APP_BOOL_JSON");
+    match err {
+        ap::Error::InvalidFlagValue { ref opt, ref value, ref origin, .. } => {
+            assert_eq!(
+                (opt.as_str(), value.as_str(), origin.as_str()),
+                ("--json", "maybe", "environment variable APP_BOOL_JSON")
+            );
+            assert_eq!(err.exit_code(), 2);
+        }
+        other => panic!("{other:?}"),
+    }
+    assert!(cli.is_ok());
+    assert!(matches!(help, Err(ap::Error::ExitMsg { code: 0, .. })));
+}
+
+#[test]
+fn counting_flag_reads_integer_env() {
+    let envv = env_basic();
+    let root = ap::CmdSpec::new("d").opt(
+        ap::OptSpec::flag("verbose", inc_verbose).short('v').env("APP_COUNT_VERBOSE").repeatable().count_from_env(),
+    );
+
+    env::set_var("APP_COUNT_VERBOSE", "3");
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &[], &mut ctx).unwrap();
+    assert_eq!(m.flag_count("verbose"), 3);
+    assert_eq!(ctx.verbose, 3);
+
+    // CLI replaces the ENV count instead of adding to it
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &argv(&["-v"]), &mut ctx).unwrap();
+    assert_eq!(m.flag_count("verbose"), 1);
+    assert_eq!(ctx.verbose, 1);
+
+    env::set_var("APP_COUNT_VERBOSE", "yes");
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &[], &mut ctx).unwrap();
+    env::remove_var("APP_COUNT_VERBOSE");
+    assert_eq!(m.flag_count("verbose"), 1);
+}
+
+#[test]
+fn env_delimiter_splits_into_occurrences() {
+    let envv = env_basic();
+    let root = ap::CmdSpec::new("d").opt(
+        ap::OptSpec::value("plugin", push_file)
+            .long("plugin")
+            .env("APP_SPLIT_PLUGINS")
+            .repeatable()
+            .delimiter(',')
+            .env_delimiter(':'),
+    );
+
+    env::set_var("APP_SPLIT_PLUGINS", "a:b,x:c");
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &[], &mut ctx).unwrap();
+    assert_eq!(ctx.files, argv(&["a", "b,x", "c"]));
+    assert_eq!(m.get_occurrences("plugin").unwrap().len(), 3);
+    assert!(m.is_set_from("plugin", ap::Source::Env));
+
+    // CLI values replace the ENV list instead of appending to it
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &argv(&["--plugin", "d,e"]), &mut ctx).unwrap();
+    env::remove_var("APP_SPLIT_PLUGINS");
+    assert_eq!(ctx.files, argv(&["d", "e"]));
+    assert!(m.is_set_from("plugin", ap::Source::Cli));
+
+    // defaults use `delimiter`
+    let root =
+        ap::CmdSpec::new("d").opt(ap::OptSpec::value("plugin", push_file).long("plugin").delimiter(',').default("x,y"));
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&envv, &root, &[], &mut ctx).unwrap();
+    assert_eq!(m.get_values("plugin").unwrap(), &argv(&["x", "y"])[..]);
+    assert_eq!(ctx.files, argv(&["x", "y"]));
+}
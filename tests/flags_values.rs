@@ -73,7 +73,10 @@ fn missing_required_value_errors() {
     let argv = argv(&["-j"]);
     let err = ap::parse(&env, &root, &argv, &mut ctx).unwrap_err();
     match err {
-        ap::Error::MissingValue { opt } => assert_eq!(opt, "-j"),
+        ap::Error::MissingValue { opt, expected, found, cmd_path } => {
+            assert_eq!((opt.as_str(), expected, found), ("-j", 1, 0));
+            assert_eq!(cmd_path, ["demo"]);
+        }
         _ => panic!("{err:?}"),
     }
 }
@@ -106,3 +109,102 @@ fn non_utf8_after_double_dash_is_positional() {
     ap::parse(&env, &root, &argv, &mut ctx).unwrap();
     assert_eq!(ctx.files.len(), 2);
 }
+
+#[test]
+fn optional_value_never_consumes_next_token() {
+    let env = env_basic();
+    let root = ap::CmdSpec::new("demo")
+        .opt(ap::OptSpec::value("color", set_limit).short('c').long("color").metavar("WHEN").optional_value("always"))
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v'))
+        .pos(ap::PosSpec::new("FILE", push_file).many());
+
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env, &root, &argv(&["--color", "never"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("always"));
+    assert_eq!(ctx.files, vec![std::ffi::OsString::from("never")]);
+    assert!(m.is_set_from("color", ap::Source::Cli));
+
+    let mut ctx = Ctx::default();
+    ap::parse(&env, &root, &argv(&["--color=never"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("never"));
+
+    // short forms: bare, attached, and at the end of a cluster
+    let mut ctx = Ctx::default();
+    ap::parse(&env, &root, &argv(&["-c", "x"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("always"));
+    assert_eq!(ctx.files.len(), 1);
+    let mut ctx = Ctx::default();
+    ap::parse(&env, &root, &argv(&["-cnever"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("never"));
+    let mut ctx = Ctx::default();
+    ap::parse(&env, &root, &argv(&["-vc=never"]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit.as_deref(), Some("never"));
+    let mut ctx = Ctx::default();
+    ap::parse(&env, &root, &argv(&["-vc"]), &mut ctx).unwrap();
+    assert_eq!((ctx.verbose, ctx.limit.as_deref()), (1, Some("always")));
+
+    let mut ctx = Ctx::default();
+    ap::parse(&env, &root, &argv(&[]), &mut ctx).unwrap();
+    assert_eq!(ctx.limit, None);
+}
+
+#[test]
+fn num_args_consumes_per_occurrence_and_keeps_grouping() {
+    let env = env_basic();
+    let root = ap::CmdSpec::new("demo")
+        .opt(ap::OptSpec::value("point", push_file).short('p').long("point").num_args(2, 2).repeatable())
+        .opt(ap::OptSpec::value("include", set_limit).long("include").num_args(1, usize::MAX))
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v'))
+        .pos(ap::PosSpec::new("INPUT", set_input).range(0, 1));
+
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env, &root, &argv(&["--point", "1", "2", "-p3", "4", "in"]), &mut ctx).unwrap();
+    assert_eq!(ctx.files, argv(&["1", "2", "3", "4"]));
+    assert_eq!(ctx.input.as_deref(), Some(OsStr::new("in")));
+    let pairs = m.get_occurrences("point").unwrap();
+    assert_eq!(pairs, vec![&argv(&["1", "2"])[..], &argv(&["3", "4"])[..]]);
+    assert_eq!(m.view().occurrences("point").unwrap().len(), 2);
+
+    // unbounded: stops at the next option or `--`; negative numbers are values
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env, &root, &argv(&["--include", "a", "-1", "c", "-v", "--", "x"]), &mut ctx).unwrap();
+    assert_eq!(m.get_values("include").unwrap(), &argv(&["a", "-1", "c"])[..]);
+    assert_eq!(ctx.verbose, 1);
+    assert_eq!(ctx.input.as_deref(), Some(OsStr::new("x")));
+}
+
+#[test]
+fn num_args_reports_expected_and_found() {
+    let root = ap::CmdSpec::new("demo")
+        .opt(ap::OptSpec::value("resize", set_limit).long("resize").num_args(2, 2))
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v'));
+    let err = ap::parse(&env_basic(), &root, &argv(&["--resize", "800", "-v"]), &mut Ctx::default()).unwrap_err();
+    match &err {
+        ap::Error::MissingValue { opt, expected, found, .. } => {
+            assert_eq!((opt.as_str(), *expected, *found), ("--resize", 2, 1));
+        }
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(err.to_string(), "option '--resize' expects 2 values, found 1");
+}
+
+#[test]
+fn delimiter_splits_each_occurrence() {
+    let root = ap::CmdSpec::new("demo").opt(
+        ap::OptSpec::value("plugin", push_file).long("plugin").repeatable().delimiter(',').validator(|v| {
+            if v.is_empty() {
+                Err("empty plugin name")
+            } else {
+                Ok(())
+            }
+        }),
+    );
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env_basic(), &root, &argv(&["--plugin", "a,b", "--plugin=c"]), &mut ctx).unwrap();
+    assert_eq!(ctx.files, argv(&["a", "b", "c"]));
+    assert_eq!(m.get_occurrences("plugin").unwrap(), vec![&argv(&["a", "b"])[..], &argv(&["c"])[..]]);
+
+    // each piece goes through the validator
+    let err = ap::parse(&env_basic(), &root, &argv(&["--plugin", "a,"]), &mut Ctx::default()).unwrap_err();
+    assert!(matches!(err, ap::Error::User(ref msg) if msg == "empty plugin name"), "{err:?}");
+}
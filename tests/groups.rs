@@ -21,7 +21,10 @@ fn xor_group_enforced() {
     let argv = argv(&["--json", "--yaml"]);
     let err = ap::parse(&env, &root, &argv, &mut ctx).unwrap_err();
     match err {
-        ap::Error::User(msg) => assert!(msg.contains("mutually exclusive")),
+        ap::Error::GroupViolation { ref group, ref given, .. } => {
+            assert_eq!((group.as_str(), given.as_slice()), ("fmt", &["--json".to_string(), "--yaml".to_string()][..]));
+            assert!(err.to_string().contains("mutually exclusive"));
+        }
         _ => panic!("{err:?}"),
     }
 }
@@ -37,7 +40,11 @@ fn reqone_group_enforced() {
     let mut ctx = Ctx::default();
     let err = ap::parse(&env, &root, &[], &mut ctx).unwrap_err();
     match err {
-        ap::Error::User(msg) => assert!(msg.contains("is required")),
+        ap::Error::GroupViolation { mode, .. } => {
+            assert_eq!(mode, ap::GroupMode::ReqOne);
+            assert_eq!(err.to_string(), "one of the options in group 'out' is required: '--quiet', '--verbose'");
+            assert_eq!(err.exit_code(), 2);
+        }
         _ => panic!("{err:?}"),
     }
 }
@@ -54,7 +61,83 @@ fn xor_group_env_vs_cli_conflict() {
     let mut ctx = Ctx::default();
     let err = ap::parse(&envv, &root, &argv(&["--yaml"]), &mut ctx).unwrap_err();
     match err {
-        ap::Error::User(msg) => assert!(msg.contains("mutually exclusive")),
+        ap::Error::GroupViolation { .. } => assert!(err.to_string().contains("mutually exclusive")),
         _ => panic!("{err:?}"),
     }
 }
+
+fn msg(err: ap::Error) -> String {
+    match err {
+        ap::Error::GroupViolation { .. } => err.to_string(),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn all_or_none_and_counted_groups() {
+    let root = ap::CmdSpec::new("login")
+        .opt(ap::OptSpec::value("user", set_input).long("user").group("creds"))
+        .opt(ap::OptSpec::value("password", set_limit).long("password").group("creds"))
+        .opt(ap::OptSpec::flag("a", set_json).short('a').group("few"))
+        .opt(ap::OptSpec::flag("b", set_json).short('b').group("few"))
+        .opt(ap::OptSpec::flag("c", set_json).short('c').group("few"))
+        .group("creds", ap::GroupMode::AllOrNone)
+        .group("few", ap::GroupMode::AtMost(2));
+    let parse = |a: &[&str]| ap::parse(&env_basic(), &root, &argv(a), &mut Ctx::default());
+
+    assert!(parse(&[]).is_ok());
+    assert!(parse(&["--user", "u", "--password", "p", "-ab"]).is_ok());
+    assert_eq!(
+        msg(parse(&["--user", "u"]).unwrap_err()),
+        "options in group 'creds' must be used together: '--user' given, '--password' missing"
+    );
+    assert_eq!(
+        msg(parse(&["-abc"]).unwrap_err()),
+        "at most 2 of the options in group 'few' may be used: '-a', '-b', '-c' were given"
+    );
+
+    let root = ap::CmdSpec::new("t")
+        .opt(ap::OptSpec::flag("a", set_json).short('a').group("g"))
+        .opt(ap::OptSpec::flag("b", set_json).short('b').group("g"))
+        .group("g", ap::GroupMode::AtLeast(2));
+    let err = ap::parse(&env_basic(), &root, &argv(&["-a"]), &mut Ctx::default()).unwrap_err();
+    assert_eq!(msg(err), "at least 2 of the options in group 'g' are required: '-a', '-b'");
+}
+
+#[test]
+fn exactly_one_with_positional_member() {
+    let root = ap::CmdSpec::new("cat")
+        .opt(ap::OptSpec::flag("stdin", set_json).long("stdin").group("input"))
+        .pos(ap::PosSpec::new("FILE", push_file).many().group("input"))
+        .group("input", ap::GroupMode::ExactlyOne);
+    let parse = |a: &[&str]| ap::parse(&env_basic(), &root, &argv(a), &mut Ctx::default());
+
+    assert!(parse(&["--stdin"]).is_ok());
+    assert!(parse(&["a.txt", "b.txt"]).is_ok());
+    assert_eq!(
+        msg(parse(&[]).unwrap_err()),
+        "exactly one of the options in group 'input' is required: '--stdin', '<FILE>'"
+    );
+    let m = msg(parse(&["--stdin", "a.txt"]).unwrap_err());
+    assert!(m.contains("mutually exclusive") && m.contains("'--stdin', '<FILE>'"), "{m}");
+
+    #[cfg(feature = "help")]
+    {
+        let env = ap::Env { wrap_cols: 200, ..env_basic() };
+        let ap::Error::ExitMsg { message: Some(help), .. } =
+            ap::parse(&env, &root, &argv(&["--help"]), &mut Ctx::default()).unwrap_err()
+        else {
+            panic!("expected help")
+        };
+        assert!(help.contains("exactly one of: --stdin, <FILE>"), "{help}");
+    }
+}
+
+#[test]
+fn defaults_do_not_conflict_in_xor_groups() {
+    let root = ap::CmdSpec::new("t")
+        .opt(ap::OptSpec::value("format", set_limit).long("format").default("json").group("fmt"))
+        .opt(ap::OptSpec::flag("raw", set_json).long("raw").group("fmt"))
+        .group("fmt", ap::GroupMode::Xor);
+    assert!(ap::parse(&env_basic(), &root, &argv(&["--raw"]), &mut Ctx::default()).is_ok());
+}
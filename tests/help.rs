@@ -27,3 +27,217 @@ fn help_emits_and_exits() {
         other => panic!("unexpected: {other:?}"),
     }
 }
+
+#[test]
+fn help_lists_inherited_global_options() {
+    let env = env_basic();
+    let root = ap::CmdSpec::new("demo")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v').long("verbose").help("Verbose").global())
+        .opt(ap::OptSpec::flag("json", set_json).long("json").help("JSON output"))
+        .subcmd(ap::CmdSpec::new("init").help("Init repo"));
+
+    let err = ap::parse(&env, &root, &argv(&["init", "--help"]), &mut Ctx::default()).unwrap_err();
+    let ap::Error::ExitMsg { message: Some(msg), .. } = err else { panic!("{err:?}") };
+    assert!(msg.contains("Inherited options"));
+    assert!(msg.contains("demo init [options]\n"), "{msg}");
+    assert!(msg.contains("--verbose"));
+    assert!(!msg.contains("--json"));
+
+    let err = ap::parse(&env, &root, &argv(&["--help"]), &mut Ctx::default()).unwrap_err();
+    let ap::Error::ExitMsg { message: Some(msg), .. } = err else { panic!("{err:?}") };
+    assert!(!msg.contains("Inherited options"));
+}
+
+#[test]
+fn help_shows_negatable_flags() {
+    let root = ap::CmdSpec::new("demo").opt(ap::OptSpec::flag("color", set_json).long("color").negatable());
+    let err = ap::parse(&env_basic(), &root, &argv(&["--help"]), &mut Ctx::default()).unwrap_err();
+    let ap::Error::ExitMsg { message: Some(msg), .. } = err else { panic!("{err:?}") };
+    assert!(msg.contains("--[no-]color"), "{msg}");
+}
+
+#[test]
+fn help_shows_optional_value_metavar() {
+    let root = ap::CmdSpec::new("demo")
+        .opt(ap::OptSpec::value("color", set_limit).short('c').long("color").metavar("WHEN").optional_value("always"));
+    let err = ap::parse(&env_basic(), &root, &argv(&["--help"]), &mut Ctx::default()).unwrap_err();
+    let ap::Error::ExitMsg { message: Some(msg), .. } = err else { panic!("{err:?}") };
+    let line = msg.lines().find(|l| l.contains("--color")).unwrap();
+    assert!(line.contains("[=") && line.contains("WHEN") && !line.contains(" WHEN"), "{line}");
+}
+
+#[test]
+fn short_and_long_help_differ() {
+    let mut env = env_basic();
+    env.wrap_cols = 40;
+    (env.version, env.author) = (None, None);
+    let root = ap::CmdSpec::new("demo")
+        .help("Demo tool")
+        .long_about("Demo tool that does a lot.\n\nRun it like this:\n\n    demo --jobs 4 file.txt")
+        .opt(
+            ap::OptSpec::value("jobs", set_jobs)
+                .long("jobs")
+                .metavar("N")
+                .default("1")
+                .help("Worker count")
+                .long_help("Number of workers to run in parallel, one per core by default.\n\nZero means auto."),
+        )
+        .pos(ap::PosSpec::new("FILE", push_file).help("Input").long_help("Input file;\n\n    - is stdin"));
+    let help = |flag: &str| {
+        let err = ap::parse(&env, &root, &argv(&[flag]), &mut Ctx::default()).unwrap_err();
+        let ap::Error::ExitMsg { message: Some(msg), .. } = err else { panic!("{err:?}") };
+        msg
+    };
+
+    let short = help("-h");
+    assert!(short.starts_with("Demo tool\n\n"), "{short}");
+    assert!(short.contains("  --jobs N    Worker count; Default: \"1\"\n"), "{short}");
+    assert!(short.contains("  FILE  Input\n"), "{short}");
+
+    let long = help("--help");
+    assert!(
+        long.starts_with("Demo tool that does a lot.\n\nRun it like this:\n\n    demo --jobs 4 file.txt\n\n"),
+        "{long}"
+    );
+    let jobs = "  --jobs N    Number of workers to run\n              in parallel, one per core\n              by default.\n\n              Zero means auto.\n\n              Default: \"1\"\n";
+    assert!(long.contains(jobs), "{long}");
+    assert!(long.contains("  FILE  Input file;\n\n            - is stdin\n"), "{long}");
+}
+
+/// (mode, variables, stdout is a tty, expected)
+type ColorCase<'a> = (ap::ColorMode, &'a [(&'a str, &'a str)], bool, bool);
+
+#[test]
+fn color_mode_honors_environment_and_terminal() {
+    use ap::ColorMode::{Always, Auto, Never};
+    let with = |vars: &[(&str, &str)]| {
+        let vars: Vec<(String, std::ffi::OsString)> = vars.iter().map(|(k, v)| ((*k).into(), (*v).into())).collect();
+        move |k: &str| vars.iter().find(|(n, _)| n == k).map(|(_, v)| v.clone())
+    };
+    let cases: &[ColorCase<'_>] = &[
+        (Auto, &[], true, true),
+        (Auto, &[], false, false),
+        (Auto, &[("NO_COLOR", "1")], true, false),
+        (Auto, &[("NO_COLOR", "")], true, true),
+        (Auto, &[("CLICOLOR", "0")], true, false),
+        (Auto, &[("CLICOLOR", "1")], false, false),
+        (Auto, &[("TERM", "dumb")], true, false),
+        (Auto, &[("CLICOLOR_FORCE", "1")], false, true),
+        (Auto, &[("CLICOLOR_FORCE", "1"), ("TERM", "dumb")], false, true),
+        (Auto, &[("CLICOLOR_FORCE", "0")], false, false),
+        (Auto, &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")], true, false),
+        (Always, &[("NO_COLOR", "1"), ("TERM", "dumb")], false, true),
+        (Never, &[("CLICOLOR_FORCE", "1")], true, false),
+    ];
+    for (mode, vars, tty, expected) in cases {
+        assert_eq!(mode.enabled_with(with(vars), *tty), *expected, "{mode:?} {vars:?} tty={tty}");
+    }
+}
+
+#[test]
+fn help_is_plain_unless_color_is_enabled() {
+    let root = ap::CmdSpec::new("demo")
+        .opt(ap::OptSpec::value("jobs", set_jobs).short('j').metavar("N"))
+        .pos(ap::PosSpec::new("FILE", push_file));
+    let plain = ap::render_help_with_path(&env_basic(), &["demo"], &root);
+    assert!(!plain.contains('\x1b'), "{plain:?}");
+    assert!(plain.starts_with("Usage:\n  demo [options] [FILE]\n"), "{plain}");
+
+    let colored = ap::render_help(&ap::Env { color: ap::ColorMode::Always, ..env_basic() }, &root);
+    assert_eq!(colored.contains('\x1b'), cfg!(feature = "color"));
+}
+
+#[test]
+fn styles_presets_and_spec_parsing() {
+    let st = ap::Styles::DEFAULT.with_spec("option=1;34:metavar=38;5;208::title=").unwrap();
+    assert_eq!(st.option.get_codes(), &[1, 34]);
+    assert_eq!(st.metavar.to_string(), "\x1b[38;5;208m");
+    assert!(st.title.is_plain());
+    assert_eq!(st.command, ap::Styles::DEFAULT.command);
+
+    let st = ap::Styles::DEFAULT.with_spec("high-contrast:error=31").unwrap();
+    assert_eq!(st.option, ap::Styles::HIGH_CONTRAST.option);
+    assert_eq!(st.error.paint("error"), "\x1b[31merror\x1b[0m");
+    assert_eq!(ap::Styles::preset("plain"), Some(ap::Styles::PLAIN));
+
+    let bad = |spec: &str| ap::Styles::DEFAULT.with_spec(spec).unwrap_err().to_string();
+    assert_eq!(bad("opton=1"), "unknown style key 'opton'");
+    assert_eq!(bad("option=bold"), "invalid style code 'bold'");
+    assert_eq!(bad("neon"), "unknown style preset 'neon'");
+
+    std::env::set_var("AP_TEST_STYLES", "option=4");
+    let st = ap::Styles::from_env("AP_TEST_STYLES");
+    std::env::set_var("AP_TEST_STYLES", "option=x");
+    let err = ap::Styles::from_env("AP_TEST_STYLES").unwrap_err();
+    std::env::remove_var("AP_TEST_STYLES");
+    assert_eq!(st.unwrap().option.get_codes(), &[4]);
+    assert_eq!(err.to_string(), "invalid AP_TEST_STYLES: invalid style code 'x'");
+    assert_eq!(ap::Styles::from_env("AP_TEST_STYLES_UNSET").unwrap(), ap::Styles::DEFAULT);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn help_uses_env_styles() {
+    let root = ap::CmdSpec::new("demo").opt(ap::OptSpec::value("jobs", set_jobs).long("jobs").metavar("N"));
+    let styles = ap::Styles { option: ap::Style::new(&[1, 34]), ..ap::Styles::DEFAULT };
+    let env = ap::Env { color: ap::ColorMode::Always, styles, ..env_basic() };
+    let help = ap::render_help_with_path(&env, &["demo"], &root);
+    assert!(help.contains("\x1b[1;34m--jobs\x1b[0m \x1b[0;96mN\x1b[0m"), "{help:?}");
+
+    let env = ap::Env { color: ap::ColorMode::Always, styles: ap::Styles::PLAIN, ..env_basic() };
+    assert!(!ap::render_help_with_path(&env, &["demo"], &root).contains('\x1b'));
+}
+
+#[test]
+fn errors_render_with_usage_and_help_tip() {
+    let root = ap::CmdSpec::new("git")
+        .opt(ap::OptSpec::flag("json", set_json).long("json"))
+        .subcmd(ap::CmdSpec::new("add").pos(ap::PosSpec::new("PATH", push_file).required()));
+    let parse = |env: &ap::Env, a: &[&str]| ap::parse(env, &root, &argv(a), &mut Ctx::default()).unwrap_err();
+
+    let err = parse(&env_basic(), &["--jsn"]);
+    assert_eq!(
+        err.render(&env_basic(), &root),
+        "error: unknown option: '--jsn'. Did you mean '--json'?\n\nUsage: git [options] <command>\n\n\
+         For more information, try '--help'.\n"
+    );
+
+    // errors record their command: its usage replaces the root's
+    let err = parse(&env_basic(), &["add"]);
+    assert_eq!(
+        err.render(&env_basic(), &root),
+        "error: missing required positional '<PATH>'\n\nUsage: git add <PATH>\n\nFor more information, try '--help'.\n"
+    );
+    let err = parse(&ap::Env { suggest: false, ..env_basic() }, &["add", "--zzz"]);
+    assert!(matches!(&err, ap::Error::UnknownOption { cmd_path, .. } if cmd_path == &["git", "add"]), "{err:?}");
+    assert_eq!(
+        err.render(&env_basic(), &root),
+        "error: unknown option: '--zzz'\n\nUsage: git add <PATH>\n\nFor more information, try '--help'.\n"
+    );
+
+    let env = ap::Env { collect_errors: true, auto_help: false, ..env_basic() };
+    let root = ap::CmdSpec::new("t")
+        .opt(ap::OptSpec::value("mode", set_limit).long("mode").choices(&["a", "b"]))
+        .opt(ap::OptSpec::value("out", set_input).long("out").required());
+    let err = ap::parse(&env, &root, &argv(&["--mode", "c"]), &mut Ctx::default()).unwrap_err();
+    let text = err.render(&env, &root);
+    assert!(
+        text.starts_with("error: missing required option: '--out'\nerror: invalid value for '--mode': 'c'"),
+        "{text}"
+    );
+    assert!(text.ends_with("\nUsage: t [options] --out <VALUE>\n"), "{text}");
+
+    let help = ap::parse(&env_basic(), &root, &argv(&["--help"]), &mut Ctx::default()).unwrap_err();
+    assert!(help.render(&env_basic(), &root).starts_with("Usage:"));
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn error_render_styles_label_and_token() {
+    let root = ap::CmdSpec::new("t").opt(ap::OptSpec::flag("json", set_json).long("json"));
+    let env = ap::Env { color: ap::ColorMode::Always, suggest: false, ..env_basic() };
+    let err = ap::parse(&env, &root, &argv(&["--bogus"]), &mut Ctx::default()).unwrap_err();
+    let text = err.render(&env, &root);
+    assert!(text.starts_with("\x1b[1;31merror:\x1b[0m unknown option: '\x1b[0;97;1m--bogus\x1b[0m'\n"), "{text:?}");
+    assert!(text.contains("try '\x1b[0;97;1m--help\x1b[0m'"), "{text:?}");
+}
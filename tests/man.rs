@@ -0,0 +1,87 @@
+#![cfg(feature = "man")]
+use rust_args_parser as ap;
+mod common;
+use common::*;
+
+fn spec<'a>() -> ap::CmdSpec<'a, Ctx> {
+    ap::CmdSpec::new("tool")
+        .help("Multitool root")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v').long("verbose").help("Verbose").global())
+        .opt(
+            ap::OptSpec::value("limit", set_limit)
+                .long("limit")
+                .metavar("N")
+                .help("Limit results")
+                .env("TOOL_LIMIT")
+                .default("42"),
+        )
+        .subcmd(
+            ap::CmdSpec::new("remote").help("Manage remotes").alias("rmt").subcmd(
+                ap::CmdSpec::new("add")
+                    .help("Add a remote")
+                    .pos(ap::PosSpec::new("NAME", set_input).required().help("Remote name")),
+            ),
+        )
+}
+
+#[test]
+fn one_page_per_command() {
+    let pages = ap::render_man(&env_basic(), &spec());
+    let names: Vec<&str> = pages.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["tool", "tool-remote", "tool-remote-add"]);
+    assert_eq!(pages[2].file_name(), "tool-remote-add.1");
+}
+
+#[test]
+fn root_page_sections() {
+    let pages = ap::render_man(&env_basic(), &spec());
+    let root = &pages[0].content;
+    assert!(root.starts_with(".TH \"TOOL\" \"1\" \"\" \"tool 0.1.0\""));
+    assert!(root.contains(".SH NAME\ntool \\- Multitool root\n"));
+    assert!(root.contains(".SH SYNOPSIS\n\\fBtool\\fR \\fI[options]\\fR \\fI<command>\\fR\n"));
+    assert!(root.contains(".SH DESCRIPTION\n"));
+    assert!(root.contains(
+        "\\fB\\-\\-limit\\fR \\fIN\\fR\nLimit results\n.br\nEnvironment: \\fBTOOL_LIMIT\\fR\n.br\nDefault: \\fI42\\fR"
+    ));
+    assert!(root.contains(".SH COMMANDS\n.TP\n\\fBremote\\fR, \\fBrmt\\fR\n"));
+    assert!(root.contains("See \\fBtool\\-remote\\fR(1)."));
+    assert!(root.contains(".SH AUTHOR\n"));
+}
+
+#[test]
+fn nested_page_synopsis_and_inherited_options() {
+    let pages = ap::render_man(&env_basic(), &spec());
+    let add = &pages[2].content;
+    assert!(add.starts_with(".TH \"TOOL\\-REMOTE\\-ADD\" \"1\""));
+    assert!(add.contains(".SH SYNOPSIS\n\\fBtool\\fR \\fBremote\\fR \\fBadd\\fR \\fI[options]\\fR \\fI<NAME>\\fR\n"));
+    assert!(add.contains(".SH INHERITED OPTIONS\n.TP\n\\fB\\-v\\fR, \\fB\\-\\-verbose\\fR\nVerbose\n"));
+    assert!(add.contains(".SH ARGUMENTS\n.TP\n\\fINAME\\fR\nRemote name\n"));
+    assert!(add.contains(".SH SEE ALSO\n\\fBtool\\-remote\\fR(1)"));
+    assert!(!add.contains("\\-\\-version"));
+}
+
+#[test]
+fn long_help_paragraphs_and_code_blocks() {
+    let root = ap::CmdSpec::new("tool")
+        .help("Multitool root")
+        .long_about("Multitool root.\n\nExample:\n\n    tool --limit 5\n    tool -v\n\nDone.")
+        .opt(
+            ap::OptSpec::value("limit", set_limit)
+                .long("limit")
+                .help("Limit")
+                .long_help("Limit results.\n\n  -1 means none"),
+        )
+        .pos(ap::PosSpec::new("FILE", push_file).help("Input").long_help("Input file.\n\nUse - for stdin."));
+    let page = &ap::render_man(&env_basic(), &root)[0].content;
+    assert!(
+        page.contains(
+            ".SH DESCRIPTION\nMultitool root.\n.PP\nExample:\n.PP\n.nf\n    tool \\-\\-limit 5\n    tool \\-v\n.fi\n.PP\nDone.\n"
+        ),
+        "{page}"
+    );
+    assert!(
+        page.contains("\\fB\\-\\-limit\\fR \\fIVALUE\\fR\nLimit results.\n.IP\n.nf\n  \\-1 means none\n.fi\n"),
+        "{page}"
+    );
+    assert!(page.contains(".TP\n\\fIFILE\\fR\nInput file.\n.IP\nUse \\- for stdin.\n"), "{page}");
+}
@@ -10,10 +10,16 @@ fn range_min_is_enforced() {
     let mut ctx = Ctx::default();
     // Only 1 provided — expect an error
     let err = ap::parse(&env, &root, &argv(&["a.txt"]), &mut ctx).unwrap_err();
-    match err {
-        ap::Error::User(msg) => assert!(msg.contains("below minimum")),
+    match &err {
+        ap::Error::TooFewPositionals { name, min: 2, got: 1, cmd_path, .. } => {
+            assert_eq!(name, "<FILE>");
+            assert_eq!(cmd_path, &["d"]);
+        }
         _ => panic!("{err:?}"),
     }
+    assert_eq!(err.to_string(), "'<FILE>' expects at least 2 values, found 1");
+    #[cfg(feature = "help")]
+    assert!(err.render(&env, &root).contains("\nUsage: d [options] <FILE...>\n"), "{err:?}");
 
     // 2..=3 should succeed
     let mut ctx = Ctx::default();
@@ -37,3 +43,28 @@ fn read_root_and_leaf_scopes() {
     let leafv = m.view();
     assert!(leafv.pos_one("X").is_some());
 }
+
+#[test]
+fn positional_errors_name_the_command() {
+    let add = ap::CmdSpec::new("add")
+        .pos(ap::PosSpec::new("NAME", push_file).required())
+        .pos(ap::PosSpec::new("URL", set_input));
+    let root = ap::CmdSpec::new("git").subcmd(ap::CmdSpec::new("remote").subcmd(add));
+    let parse = |a: &[&str]| ap::parse(&env_basic(), &root, &argv(a), &mut Ctx::default()).unwrap_err();
+
+    let err = parse(&["remote", "add"]);
+    assert!(
+        matches!(&err, ap::Error::MissingPositional { name, cmd_path, .. } if name == "<NAME>" && cmd_path == &["git", "remote", "add"]),
+        "{err:?}"
+    );
+    assert_eq!(err.to_string(), "missing required positional '<NAME>'");
+    #[cfg(feature = "help")]
+    assert!(err.render(&env_basic(), &root).contains("\nUsage: git remote add <NAME> [URL]\n"), "{err:?}");
+
+    let err = parse(&["remote", "add", "origin", "url", "extra"]);
+    assert!(matches!(&err, ap::Error::UnexpectedPositional { token, max: 2, .. } if token == "extra"), "{err:?}");
+    assert_eq!(err.to_string(), "unexpected positional 'extra': 'git remote add' takes at most 2 positionals");
+
+    let err = ap::parse(&env_basic(), &ap::CmdSpec::new("t"), &argv(&["x"]), &mut Ctx::default()).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected positional 'x': 't' takes no positionals");
+}
@@ -0,0 +1,105 @@
+use rust_args_parser as ap;
+mod common;
+use common::*;
+
+fn spec<'a>() -> ap::CmdSpec<'a, Ctx> {
+    ap::CmdSpec::new("tls")
+        .opt(ap::OptSpec::value("key", set_input).long("key").requires("cert"))
+        .opt(ap::OptSpec::value("cert", set_limit).long("cert"))
+        .opt(ap::OptSpec::flag("stdin", set_json).long("stdin").conflicts_with("FILE"))
+        .opt(ap::OptSpec::value("output", set_limit).short('o').long("output").required_unless("dry_run"))
+        .opt(ap::OptSpec::flag("dry_run", inc_verbose).long("dry-run"))
+        .opt(ap::OptSpec::value("auth", set_limit).long("auth").default("none"))
+        .opt(ap::OptSpec::value("token", set_limit).long("token").required_if("auth", "token"))
+        .pos(ap::PosSpec::new("FILE", push_file).many())
+}
+
+fn err(args: &[&str]) -> ap::Error {
+    ap::parse(&env_basic(), &spec(), &argv(args), &mut Ctx::default()).unwrap_err()
+}
+
+#[test]
+fn requires_and_conflicts_use_cli_spellings() {
+    let e = err(&["--dry-run", "--key", "k.pem"]);
+    assert!(
+        matches!(&e, ap::Error::MissingRequired { arg, reason, .. } if arg == "--cert" && reason == "required by '--key'")
+    );
+    assert_eq!(e.to_string(), "missing '--cert': required by '--key'");
+
+    let e = err(&["--dry-run", "--stdin", "a.txt"]);
+    assert!(
+        matches!(&e, ap::Error::ArgumentConflict { arg, other, .. } if arg == "--stdin" && other == "<FILE>"),
+        "{e:?}"
+    );
+    assert_eq!(e.to_string(), "'--stdin' cannot be used with '<FILE>'");
+
+    assert!(ap::parse(&env_basic(), &spec(), &argv(&["--dry-run", "--key", "k", "--cert", "c"]), &mut Ctx::default())
+        .is_ok());
+    assert!(ap::parse(&env_basic(), &spec(), &argv(&["--dry-run", "--stdin"]), &mut Ctx::default()).is_ok());
+}
+
+#[test]
+fn required_unless_and_required_if() {
+    let e = err(&[]);
+    assert_eq!(e.to_string(), "missing '--output': required unless '--dry-run' is given");
+    assert!(ap::parse(&env_basic(), &spec(), &argv(&["-o", "out"]), &mut Ctx::default()).is_ok());
+
+    let e = err(&["--dry-run", "--auth=token"]);
+    assert!(matches!(&e, ap::Error::MissingRequired { arg, .. } if arg == "--token"), "{e:?}");
+    assert_eq!(e.to_string(), "missing '--token': required when '--auth' is 'token'");
+    assert!(ap::parse(
+        &env_basic(),
+        &spec(),
+        &argv(&["--dry-run", "--auth=token", "--token", "t"]),
+        &mut Ctx::default()
+    )
+    .is_ok());
+}
+
+#[test]
+fn required_options_are_reported_together_after_overlays() {
+    let root = ap::CmdSpec::new("deploy")
+        .opt(ap::OptSpec::value("target", set_input).long("target").metavar("HOST").required())
+        .opt(ap::OptSpec::value("token", set_limit).short('t').env("DEPLOY_REQ_TOKEN").required())
+        .opt(ap::OptSpec::value("region", set_limit).long("region").default("eu").required())
+        .opt(ap::OptSpec::flag("json", set_json).long("json"));
+    let parse = |a: &[&str]| ap::parse(&env_basic(), &root, &argv(a), &mut Ctx::default());
+
+    let e = parse(&["--json"]).unwrap_err();
+    assert!(matches!(&e, ap::Error::MissingRequiredOptions { opts, .. } if opts == &["--target", "-t"]), "{e:?}");
+    assert_eq!(e.to_string(), "missing required options: '--target', '-t'");
+    assert_eq!(parse(&["-t", "x"]).unwrap_err().to_string(), "missing required option: '--target'");
+
+    std::env::set_var("DEPLOY_REQ_TOKEN", "secret");
+    let m = parse(&["--target", "web1"]);
+    std::env::remove_var("DEPLOY_REQ_TOKEN");
+    assert!(m.unwrap().is_set_from("region", ap::Source::Default));
+
+    #[cfg(feature = "help")]
+    {
+        let env = ap::Env { wrap_cols: 200, ..env_basic() };
+        let ap::Error::ExitMsg { message: Some(help), .. } =
+            ap::parse(&env, &root, &argv(&["--help"]), &mut Ctx::default()).unwrap_err()
+        else {
+            panic!("expected help")
+        };
+        assert!(help.contains("deploy [options] --target <HOST> -t <VALUE> --region <VALUE>"), "{help}");
+    }
+}
+
+#[test]
+fn relations_resolve_inherited_globals_and_reject_unknown_names() {
+    let root = ap::CmdSpec::new("tool")
+        .opt(ap::OptSpec::value("token", set_limit).long("token").global())
+        .subcmd(ap::CmdSpec::new("push").opt(ap::OptSpec::flag("force", set_json).long("force").requires("token")));
+    let parse = |a: &[&str]| ap::parse(&env_basic(), &root, &argv(a), &mut Ctx::default());
+    assert_eq!(parse(&["push", "--force"]).unwrap_err().to_string(), "missing '--token': required by '--force'");
+    assert!(parse(&["--token", "t", "push", "--force"]).is_ok());
+    assert!(parse(&["push", "--token", "t", "--force"]).is_ok());
+
+    // a typo in the spec is reported even when the rule does not fire
+    let root = ap::CmdSpec::<Ctx>::new("tls").opt(ap::OptSpec::value("key", set_input).long("key").requires("cret"));
+    let e = ap::parse(&env_basic(), &root, &[], &mut Ctx::default()).unwrap_err();
+    assert!(matches!(&e, ap::Error::Parse(msg) if msg == "'--key' refers to unknown argument 'cret'"), "{e:?}");
+    assert_eq!(e.exit_code(), 70);
+}
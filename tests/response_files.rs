@@ -0,0 +1,71 @@
+use rust_args_parser as ap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+mod common;
+use common::*;
+
+fn env_rsp() -> ap::Env {
+    ap::Env { response_files: true, ..env_basic() }
+}
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rap-rsp-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn spec<'a>() -> ap::CmdSpec<'a, Ctx> {
+    ap::CmdSpec::new("cc")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v'))
+        .opt(ap::OptSpec::value("jobs", set_jobs).short('j').long("jobs"))
+        .pos(ap::PosSpec::new("FILE", push_file).many())
+}
+
+#[test]
+fn response_file_expands_with_quotes_comments_and_nesting() {
+    let dir = scratch("expand");
+    std::fs::write(dir.join("inner.rsp"), "'with space.c' \"q\\\"uote.c\"\n").unwrap();
+    std::fs::write(dir.join("args.rsp"), "# build flags\n-v --jobs 4 # trailing comment\n@inner.rsp a\\ b.c\n")
+        .unwrap();
+    let at = format!("@{}", dir.join("args.rsp").display());
+
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env_rsp(), &spec(), &argv(&["first.c", &at, "last.c"]), &mut ctx).unwrap();
+    assert_eq!(ctx.verbose, 1);
+    assert_eq!(ctx.jobs, Some(4));
+    assert_eq!(ctx.files, argv(&["first.c", "with space.c", "q\"uote.c", "a b.c", "last.c"]));
+    assert_eq!(m.get_response_file("jobs"), Some(dir.join("args.rsp").as_path()));
+    assert!(m.is_set_from("jobs", ap::Source::Cli));
+    // the last FILE came from the command line itself
+    assert_eq!(m.view().response_file("FILE"), None);
+
+    // disabled by default; never expanded after `--`
+    let mut ctx = Ctx::default();
+    ap::parse(&env_basic(), &spec(), &argv(&[&at]), &mut ctx).unwrap();
+    assert_eq!(ctx.files, argv(&[&at]));
+    let mut ctx = Ctx::default();
+    ap::parse(&env_rsp(), &spec(), &argv(&["--", &at]), &mut ctx).unwrap();
+    assert_eq!(ctx.files.last().map(std::ffi::OsString::as_os_str), Some(OsStr::new(&at)));
+}
+
+#[test]
+fn response_file_errors_name_file_and_line() {
+    let dir = scratch("errors");
+    std::fs::write(dir.join("quote.rsp"), "-v\n'open\n").unwrap();
+    std::fs::write(dir.join("a.rsp"), "-v\n@b.rsp\n").unwrap();
+    std::fs::write(dir.join("b.rsp"), "@a.rsp\n").unwrap();
+    let run = |p: &Path| {
+        let at = format!("@{}", p.display());
+        match ap::parse(&env_rsp(), &spec(), &argv(&[&at]), &mut Ctx::default()) {
+            Err(ap::Error::User(msg)) => msg,
+            other => panic!("{other:?}"),
+        }
+    };
+
+    let msg = run(&dir.join("quote.rsp"));
+    assert!(msg.contains("quote.rsp:2:") && msg.contains("unterminated quote"), "{msg}");
+    let msg = run(&dir.join("a.rsp"));
+    assert!(msg.contains("a.rsp:2:") && msg.contains("includes itself"), "{msg}");
+    let msg = run(&dir.join("missing.rsp"));
+    assert!(msg.contains("missing.rsp"), "{msg}");
+}
@@ -0,0 +1,108 @@
+# bash completion for git
+_git() {
+    local cur prev cmd i w
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd="git"
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        w="${COMP_WORDS[i]}"
+        case "${cmd},${w}" in
+            git,init) cmd="git__init" ;;
+            git,add) cmd="git__add" ;;
+            git,commit) cmd="git__commit" ;;
+            git,status) cmd="git__status" ;;
+            git,log) cmd="git__log" ;;
+            git,branch) cmd="git__branch" ;;
+            git,checkout) cmd="git__checkout" ;;
+            git,-C|git,-c) ((i++)) ;;
+            git__init,--template) ((i++)) ;;
+            git__commit,-m|git__commit,--message) ((i++)) ;;
+            git__log,-n|git__log,--grep) ((i++)) ;;
+            git__branch,-d) ((i++)) ;;
+            git__checkout,-b) ((i++)) ;;
+        esac
+    done
+    case "${cmd}" in
+        git)
+            case "${prev}" in
+                -C|-c)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0 ;;
+            esac
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help -V --version -A --author -C -c --no-pager" -- "${cur}"))
+            else
+                COMPREPLY=($(compgen -W "init add commit status log branch checkout" -- "${cur}"))
+            fi
+            ;;
+        git__init)
+            case "${prev}" in
+                --template)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0 ;;
+            esac
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help --bare --template" -- "${cur}"))
+            else
+                COMPREPLY=($(compgen -f -- "${cur}"))
+            fi
+            ;;
+        git__add)
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help -A --all -p --patch -n --dry-run" -- "${cur}"))
+            else
+                COMPREPLY=($(compgen -f -- "${cur}"))
+            fi
+            ;;
+        git__commit)
+            case "${prev}" in
+                -m|--message)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0 ;;
+            esac
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help -m --message -a --all --amend" -- "${cur}"))
+            fi
+            ;;
+        git__status)
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help -s --short --branch" -- "${cur}"))
+            fi
+            ;;
+        git__log)
+            case "${prev}" in
+                -n|--grep)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0 ;;
+            esac
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help --oneline -n --grep" -- "${cur}"))
+            else
+                COMPREPLY=($(compgen -f -- "${cur}"))
+            fi
+            ;;
+        git__branch)
+            case "${prev}" in
+                -d)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0 ;;
+            esac
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help --list --all -d" -- "${cur}"))
+            fi
+            ;;
+        git__checkout)
+            case "${prev}" in
+                -b)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0 ;;
+            esac
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "-h --help -b" -- "${cur}"))
+            else
+                COMPREPLY=($(compgen -f -- "${cur}"))
+            fi
+            ;;
+    esac
+}
+complete -F _git -o bashdefault -o default git
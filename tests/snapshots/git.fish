@@ -0,0 +1,94 @@
+# fish completion for git
+function __git_cmd_path
+    set -l tokens (commandline -opc)
+    set -l path git
+    set -l skip 0
+    for t in $tokens[2..-1]
+        if test $skip = 1
+            set skip 0
+            continue
+        end
+        switch "$path,$t"
+            case 'git,init'
+                set path git__init
+            case 'git,add'
+                set path git__add
+            case 'git,commit'
+                set path git__commit
+            case 'git,status'
+                set path git__status
+            case 'git,log'
+                set path git__log
+            case 'git,branch'
+                set path git__branch
+            case 'git,checkout'
+                set path git__checkout
+            case 'git,-C' 'git,-c'
+                set skip 1
+            case 'git__init,--template'
+                set skip 1
+            case 'git__commit,-m' 'git__commit,--message'
+                set skip 1
+            case 'git__log,-n' 'git__log,--grep'
+                set skip 1
+            case 'git__branch,-d'
+                set skip 1
+            case 'git__checkout,-b'
+                set skip 1
+        end
+    end
+    echo $path
+end
+
+function __git_at
+    test (__git_cmd_path) = $argv[1]
+end
+
+complete -c git -n '__git_at git' -f -a 'init' -d 'create an empty Git repository'
+complete -c git -n '__git_at git' -f -a 'add' -d 'add file contents to the index'
+complete -c git -n '__git_at git' -f -a 'commit' -d 'record changes to the repository'
+complete -c git -n '__git_at git' -f -a 'status'
+complete -c git -n '__git_at git' -f -a 'log'
+complete -c git -n '__git_at git' -f -a 'branch'
+complete -c git -n '__git_at git' -f -a 'checkout'
+complete -c git -n '__git_at git' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git' -s V -l version -d 'Show version and exit'
+complete -c git -n '__git_at git' -s A -l author -d 'Show author and exit'
+complete -c git -n '__git_at git' -s C -r -d 'run as if git was started in DIR'
+complete -c git -n '__git_at git' -s c -r -d 'set config on the command line'
+complete -c git -n '__git_at git' -l no-pager -d 'do not pipe output into a pager'
+
+complete -c git -n '__git_at git__init' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git__init' -l bare -d 'create a bare repository'
+complete -c git -n '__git_at git__init' -l template -r
+complete -c git -n '__git_at git__init' -F -d 'directory'
+
+complete -c git -n '__git_at git__add' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git__add' -s A -l all -d 'stage all tracked and untracked'
+complete -c git -n '__git_at git__add' -s p -l patch -d 'interactive hunk selection'
+complete -c git -n '__git_at git__add' -s n -l dry-run -d 'dry run'
+complete -c git -n '__git_at git__add' -F -d 'paths to add'
+
+complete -c git -n '__git_at git__commit' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git__commit' -s m -l message -r
+complete -c git -n '__git_at git__commit' -s a -l all
+complete -c git -n '__git_at git__commit' -l amend
+
+complete -c git -n '__git_at git__status' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git__status' -s s -l short
+complete -c git -n '__git_at git__status' -l branch
+
+complete -c git -n '__git_at git__log' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git__log' -l oneline
+complete -c git -n '__git_at git__log' -s n -r
+complete -c git -n '__git_at git__log' -l grep -r
+complete -c git -n '__git_at git__log' -F -d 'PATH'
+
+complete -c git -n '__git_at git__branch' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git__branch' -l list
+complete -c git -n '__git_at git__branch' -l all
+complete -c git -n '__git_at git__branch' -s d -r
+
+complete -c git -n '__git_at git__checkout' -s h -l help -d 'Show this help and exit'
+complete -c git -n '__git_at git__checkout' -s b -r
+complete -c git -n '__git_at git__checkout' -F -d 'BRANCH'
@@ -0,0 +1,102 @@
+using namespace System.Management.Automation
+
+Register-ArgumentCompleter -Native -CommandName 'git' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $path = 'git'
+    $skip = $false
+    foreach ($el in $commandAst.CommandElements | Select-Object -Skip 1) {
+        if ($el.Extent.EndOffset -ge $cursorPosition) { break }
+        $t = $el.ToString()
+        if ($skip) { $skip = $false; continue }
+        switch -CaseSensitive ("$path,$t") {
+            'git,init' { $path = 'git__init'; break }
+            'git,add' { $path = 'git__add'; break }
+            'git,commit' { $path = 'git__commit'; break }
+            'git,status' { $path = 'git__status'; break }
+            'git,log' { $path = 'git__log'; break }
+            'git,branch' { $path = 'git__branch'; break }
+            'git,checkout' { $path = 'git__checkout'; break }
+            'git,-C' { $skip = $true; break }
+            'git,-c' { $skip = $true; break }
+            'git__init,--template' { $skip = $true; break }
+            'git__commit,-m' { $skip = $true; break }
+            'git__commit,--message' { $skip = $true; break }
+            'git__log,-n' { $skip = $true; break }
+            'git__log,--grep' { $skip = $true; break }
+            'git__branch,-d' { $skip = $true; break }
+            'git__checkout,-b' { $skip = $true; break }
+        }
+    }
+    $candidates = switch ($path) {
+        'git' {
+            [CompletionResult]::new('init', 'init', [CompletionResultType]::ParameterValue, 'create an empty Git repository')
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'add file contents to the index')
+            [CompletionResult]::new('commit', 'commit', [CompletionResultType]::ParameterValue, 'record changes to the repository')
+            [CompletionResult]::new('status', 'status', [CompletionResultType]::ParameterValue, 'status')
+            [CompletionResult]::new('log', 'log', [CompletionResultType]::ParameterValue, 'log')
+            [CompletionResult]::new('branch', 'branch', [CompletionResultType]::ParameterValue, 'branch')
+            [CompletionResult]::new('checkout', 'checkout', [CompletionResultType]::ParameterValue, 'checkout')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('-V', '-V', [CompletionResultType]::ParameterName, 'Show version and exit')
+            [CompletionResult]::new('--version', '--version', [CompletionResultType]::ParameterName, 'Show version and exit')
+            [CompletionResult]::new('-A', '-A', [CompletionResultType]::ParameterName, 'Show author and exit')
+            [CompletionResult]::new('--author', '--author', [CompletionResultType]::ParameterName, 'Show author and exit')
+            [CompletionResult]::new('-C', '-C', [CompletionResultType]::ParameterName, 'run as if git was started in DIR')
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'set config on the command line')
+            [CompletionResult]::new('--no-pager', '--no-pager', [CompletionResultType]::ParameterName, 'do not pipe output into a pager')
+        }
+        'git__init' {
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--bare', '--bare', [CompletionResultType]::ParameterName, 'create a bare repository')
+            [CompletionResult]::new('--template', '--template', [CompletionResultType]::ParameterName, '--template')
+        }
+        'git__add' {
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('-A', '-A', [CompletionResultType]::ParameterName, 'stage all tracked and untracked')
+            [CompletionResult]::new('--all', '--all', [CompletionResultType]::ParameterName, 'stage all tracked and untracked')
+            [CompletionResult]::new('-p', '-p', [CompletionResultType]::ParameterName, 'interactive hunk selection')
+            [CompletionResult]::new('--patch', '--patch', [CompletionResultType]::ParameterName, 'interactive hunk selection')
+            [CompletionResult]::new('-n', '-n', [CompletionResultType]::ParameterName, 'dry run')
+            [CompletionResult]::new('--dry-run', '--dry-run', [CompletionResultType]::ParameterName, 'dry run')
+        }
+        'git__commit' {
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('-m', '-m', [CompletionResultType]::ParameterName, '-m')
+            [CompletionResult]::new('--message', '--message', [CompletionResultType]::ParameterName, '--message')
+            [CompletionResult]::new('-a', '-a', [CompletionResultType]::ParameterName, '-a')
+            [CompletionResult]::new('--all', '--all', [CompletionResultType]::ParameterName, '--all')
+            [CompletionResult]::new('--amend', '--amend', [CompletionResultType]::ParameterName, '--amend')
+        }
+        'git__status' {
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('-s', '-s', [CompletionResultType]::ParameterName, '-s')
+            [CompletionResult]::new('--short', '--short', [CompletionResultType]::ParameterName, '--short')
+            [CompletionResult]::new('--branch', '--branch', [CompletionResultType]::ParameterName, '--branch')
+        }
+        'git__log' {
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--oneline', '--oneline', [CompletionResultType]::ParameterName, '--oneline')
+            [CompletionResult]::new('-n', '-n', [CompletionResultType]::ParameterName, '-n')
+            [CompletionResult]::new('--grep', '--grep', [CompletionResultType]::ParameterName, '--grep')
+        }
+        'git__branch' {
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--list', '--list', [CompletionResultType]::ParameterName, '--list')
+            [CompletionResult]::new('--all', '--all', [CompletionResultType]::ParameterName, '--all')
+            [CompletionResult]::new('-d', '-d', [CompletionResultType]::ParameterName, '-d')
+        }
+        'git__checkout' {
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Show this help and exit')
+            [CompletionResult]::new('-b', '-b', [CompletionResultType]::ParameterName, '-b')
+        }
+    }
+    $candidates | Where-Object { $_.CompletionText -like "$wordToComplete*" }
+}
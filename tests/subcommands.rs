@@ -41,7 +41,7 @@ fn unknown_subcommand_suggests() {
     let argv = argv(&["remot"]);
     let err = ap::parse(&env, &root, &argv, &mut ctx).unwrap_err();
     match err {
-        ap::Error::UnknownCommand { token, suggestions } => {
+        ap::Error::UnknownCommand { token, suggestions, .. } => {
             assert_eq!(token, "remot");
             assert!(suggestions.iter().any(|s| s == "remote"));
         }
@@ -63,3 +63,37 @@ fn root_option_not_recognized_after_descent() {
         _ => panic!("{err:?}"),
     }
 }
+
+#[test]
+fn global_option_accepted_after_descent() {
+    let env = env_basic();
+    let root = ap::CmdSpec::new("t")
+        .opt(ap::OptSpec::flag("verbose", inc_verbose).short('v').long("verbose").global())
+        .opt(ap::OptSpec::value("limit", set_limit).long("limit").global())
+        .subcmd(ap::CmdSpec::new("sub").subcmd(ap::CmdSpec::new("leaf").pos(ap::PosSpec::new("X", push_file))));
+    let mut ctx = Ctx::default();
+    let argv = argv(&["-v", "sub", "--verbose", "leaf", "-v", "--limit=9", "z"]);
+    let m = ap::parse(&env, &root, &argv, &mut ctx).expect("parse");
+    assert_eq!(ctx.verbose, 3);
+    assert_eq!(ctx.limit.as_deref(), Some("9"));
+    assert_eq!(ctx.files.len(), 1);
+
+    // Stored under the declaring (root) scope, not the leaf.
+    let rootv = m.at(&[]);
+    assert_eq!(rootv.flag_count("verbose"), 3);
+    assert!(rootv.is_set_from("limit", ap::Source::Cli));
+    assert!(!m.view().is_set("limit"));
+}
+
+#[test]
+fn subcommand_option_shadows_global() {
+    let env = env_basic();
+    let root = ap::CmdSpec::new("t")
+        .opt(ap::OptSpec::flag("json", set_json).long("json").global())
+        .subcmd(ap::CmdSpec::new("sub").opt(ap::OptSpec::flag("json2", inc_verbose).long("json")));
+    let mut ctx = Ctx::default();
+    let m = ap::parse(&env, &root, &argv(&["sub", "--json"]), &mut ctx).unwrap();
+    assert!(!ctx.json);
+    assert_eq!(ctx.verbose, 1);
+    assert!(m.view().is_set("json2"));
+}
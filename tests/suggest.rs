@@ -15,7 +15,7 @@ fn unknown_options_and_commands_suggest() {
     let mut ctx = Ctx::default();
     let err = ap::parse(&env, &root, &argv(&["--helme"]), &mut ctx).unwrap_err();
     match err {
-        ap::Error::UnknownOption { token, suggestions } => {
+        ap::Error::UnknownOption { token, suggestions, .. } => {
             assert_eq!(token, "--helme");
             assert!(suggestions.iter().any(|s| s == "--helpme"));
         }
@@ -26,7 +26,7 @@ fn unknown_options_and_commands_suggest() {
     let mut ctx = Ctx::default();
     let err = ap::parse(&env, &root, &argv(&["remot"]), &mut ctx).unwrap_err();
     match err {
-        ap::Error::UnknownCommand { token, suggestions } => {
+        ap::Error::UnknownCommand { token, suggestions, .. } => {
             assert_eq!(token, "remot");
             assert!(suggestions.iter().any(|s| s == "remote"));
         }
@@ -41,7 +41,7 @@ fn unknown_has_no_suggestions_when_feature_off() {
     let root = ap::CmdSpec::new("t").opt(ap::OptSpec::flag("helpme", |_| {}).long("helpme"));
     let err = ap::parse(&env, &root, &argv(&["--helme"]), &mut Ctx::default()).unwrap_err();
     match err {
-        ap::Error::UnknownOption { token, suggestions } => {
+        ap::Error::UnknownOption { token, suggestions, .. } => {
             assert_eq!(token, "--helme");
             assert!(suggestions.is_empty());
         }
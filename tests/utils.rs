@@ -18,3 +18,13 @@ fn strip_ansi_len_ignores_codes() {
     let s = "\x1b[1mHello\x1b[0m world"; // bold Hello
     assert_eq!(sl(s), "Hello world".len());
 }
+
+#[test]
+fn parse_bool_spellings() {
+    use ap::util::parse_bool;
+    assert_eq!(parse_bool(" Yes "), Some(true));
+    assert_eq!(parse_bool("ON"), Some(true));
+    assert_eq!(parse_bool(""), Some(false));
+    assert_eq!(parse_bool("0"), Some(false));
+    assert_eq!(parse_bool("2"), None);
+}
@@ -54,3 +54,32 @@ fn validator_runs_on_env_before_callbacks() {
     }
     assert!(ctx.limit.is_none(), "callback must not fire on invalid env value");
 }
+
+#[test]
+fn collect_errors_reports_every_level_and_skips_callbacks() {
+    let env = ap::Env { collect_errors: true, ..env_basic() };
+    let root = ap::CmdSpec::new("d")
+        .opt(ap::OptSpec::value("name", set_limit).long("name").validator(non_empty))
+        .opt(ap::OptSpec::flag("json", set_json).long("json").required())
+        .subcmd(
+            ap::CmdSpec::new("run")
+                .opt(ap::OptSpec::value("jobs", set_jobs).long("jobs").choices(&["1", "2"]))
+                .pos(ap::PosSpec::new("FILE", push_file).required()),
+        );
+
+    let mut ctx = Ctx::default();
+    let err = ap::parse(&env, &root, &argv(&["--name=", "run", "--jobs", "9"]), &mut ctx).unwrap_err();
+    let ap::Error::Multiple(errors) = &err else { panic!("{err:?}") };
+    assert_eq!(errors.len(), 4, "{err}");
+    assert!(matches!(errors[0], ap::Error::MissingRequiredOptions { .. }));
+    assert!(matches!(errors[2], ap::Error::MissingPositional { ref name, .. } if name == "<FILE>"));
+    assert!(matches!(errors[3], ap::Error::InvalidChoice { .. }));
+    assert!(err.to_string().starts_with("4 errors:\n  1. missing required option: '--json'\n  2. empty\n"), "{err}");
+    assert_eq!(ctx.limit, None, "callbacks must not run");
+
+    // a single failure is not wrapped; without the setting the first error wins
+    let err = ap::parse(&env, &root, &argv(&["--json", "--name="]), &mut Ctx::default()).unwrap_err();
+    assert!(matches!(err, ap::Error::User(ref m) if m == "empty"), "{err:?}");
+    let err = ap::parse(&env_basic(), &root, &argv(&["--name="]), &mut Ctx::default()).unwrap_err();
+    assert!(matches!(err, ap::Error::MissingRequiredOptions { .. }), "{err:?}");
+}